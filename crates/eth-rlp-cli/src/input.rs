@@ -0,0 +1,131 @@
+use clap::ValueEnum;
+use eth_rlp_types::BlockHeader;
+use eth_rlp_verify::ingest::{ColumnMapping, CsvReader, IngestError, JsonlReader};
+use std::io::{BufReader, Read};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum InputError {
+    #[error("Cannot infer the format of {0}, use --format")]
+    UnknownFormat(String),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Ingest error: {0}")]
+    IngestError(#[from] IngestError),
+}
+
+/// The file formats block headers can be read from.
+///
+/// Every format holds `BlockHeader` records with the field names of the database schema. JSONL
+/// and CSV files are read with the `ingest` readers of `eth-rlp-verify`, so that quantities may
+/// also be given in decimal.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Format {
+    /// A JSON array of headers.
+    Json,
+    /// One JSON header per line.
+    Jsonl,
+    /// A CSV file with a header row.
+    Csv,
+}
+
+impl Format {
+    /// Infers the format from a file extension.
+    pub fn from_path(path: &Path) -> Result<Self, InputError> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Ok(Format::Json),
+            Some("jsonl") | Some("ndjson") => Ok(Format::Jsonl),
+            Some("csv") => Ok(Format::Csv),
+            _ => Err(InputError::UnknownFormat(path.display().to_string())),
+        }
+    }
+}
+
+/// Reads block headers from `reader`.
+///
+/// JSONL and CSV inputs are streamed one record at a time, so that large dumps can be verified
+/// in constant memory. JSON arrays are read in full.
+///
+/// # Returns
+///
+/// An iterator over the parsed headers, in file order.
+pub fn read_headers<'a, R: Read + 'a>(
+    reader: R,
+    format: Format,
+) -> Result<Box<dyn Iterator<Item = Result<BlockHeader, InputError>> + 'a>, InputError> {
+    match format {
+        Format::Json => {
+            let headers: Vec<BlockHeader> = serde_json::from_reader(reader)?;
+            Ok(Box::new(headers.into_iter().map(Ok)))
+        }
+        Format::Jsonl => Ok(Box::new(
+            JsonlReader::new(BufReader::new(reader), ColumnMapping::default())
+                .map(|header| Ok(header?)),
+        )),
+        Format::Csv => Ok(Box::new(
+            CsvReader::new(reader, ColumnMapping::default())?.map(|header| Ok(header?)),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_rlp_verify::test_helpers::create_test_chain;
+
+    fn headers() -> Vec<BlockHeader> {
+        create_test_chain(0, 3)
+    }
+
+    fn read_all(data: &[u8], format: Format) -> Vec<BlockHeader> {
+        read_headers(data, format)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_read_json_formats() {
+        let headers = headers();
+        let json = serde_json::to_vec(&headers).unwrap();
+        assert_eq!(read_all(&json, Format::Json), headers);
+
+        let jsonl: String = headers
+            .iter()
+            .map(|header| serde_json::to_string(header).unwrap() + "\n\n")
+            .collect();
+        assert_eq!(read_all(jsonl.as_bytes(), Format::Jsonl), headers);
+
+        // Exports may give quantities in decimal.
+        let decimal = serde_json::to_string(&headers[0])
+            .unwrap()
+            .replace("\"0x400000000\"", "17179869184");
+        assert_eq!(read_all(decimal.as_bytes(), Format::Jsonl), headers[..1]);
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let headers = headers();
+        let mut writer = csv::Writer::from_writer(vec![]);
+        for header in &headers {
+            writer.serialize(header).unwrap();
+        }
+        let csv = writer.into_inner().unwrap();
+        assert_eq!(read_all(&csv, Format::Csv), headers);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            Format::from_path(Path::new("blocks.jsonl")).unwrap(),
+            Format::Jsonl
+        );
+        assert!(matches!(
+            Format::from_path(Path::new("blocks.txt")),
+            Err(InputError::UnknownFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+mod input;
+
+use clap::{Parser, Subcommand};
+use eth_rlp_verify::chain::{BlockResult, ChainVerifier};
+use eth_rlp_verify::decode_block_header;
+use eth_rlp_verify::proofs::keccak256;
+use input::{read_headers, Format, InputError};
+use primitive_types::{H256, U256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// Verifies Ethereum block headers across eras.
+#[derive(Parser)]
+#[command(name = "eth-rlp-verify", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Verifies the hashes of block headers read from a JSON, JSONL or CSV file.
+    Headers {
+        /// The file to read, or `-` for standard input.
+        path: PathBuf,
+        /// The file format, inferred from the extension by default.
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Also checks that every header links to the previous one.
+        #[arg(long)]
+        chain: bool,
+        /// Only prints failures and the summary.
+        #[arg(long)]
+        quiet: bool,
+    },
+    /// Checks RLP encoded block headers given as hex.
+    Rlp {
+        /// The RLP encoded headers.
+        #[arg(required = true)]
+        headers: Vec<String>,
+        /// The expected hash of each header, in the same order. Without it headers are only decoded.
+        #[arg(long = "hash")]
+        hashes: Vec<String>,
+    },
+}
+
+/// Describes the outcome of a block, or returns `None` if it is valid.
+fn failure(result: &BlockResult, chain: bool) -> Option<&'static str> {
+    if !result.hash_valid {
+        Some("invalid hash")
+    } else if chain && !result.linked {
+        Some("broken link")
+    } else {
+        None
+    }
+}
+
+fn verify_headers(
+    path: PathBuf,
+    format: Option<Format>,
+    chain: bool,
+    quiet: bool,
+) -> Result<bool, InputError> {
+    let format = match format {
+        Some(format) => format,
+        None => Format::from_path(&path)?,
+    };
+    let reader: Box<dyn Read> = if path.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        Box::new(File::open(&path)?)
+    };
+    check_headers(reader, format, chain, quiet, &mut std::io::stdout().lock())
+}
+
+/// Verifies the headers read from `reader`, writing one line per block and a summary to `out`.
+///
+/// # Returns
+///
+/// `true` if every header is valid, and linked to its parent when `chain` is set.
+fn check_headers<R: Read, W: Write>(
+    reader: R,
+    format: Format,
+    chain: bool,
+    quiet: bool,
+    out: &mut W,
+) -> Result<bool, InputError> {
+    let mut verifier = ChainVerifier::new();
+    let (mut count, mut failures) = (0u64, 0u64);
+    for block_header in read_headers(reader, format)? {
+        let result = verifier.push(block_header?);
+        count += 1;
+        match failure(&result, chain) {
+            Some(reason) => {
+                failures += 1;
+                writeln!(out, "{} {} {}", result.number, result.block_hash, reason)?;
+            }
+            None if !quiet => writeln!(out, "{} {} ok", result.number, result.block_hash)?,
+            None => {}
+        }
+    }
+
+    writeln!(out, "verified {count} headers, {failures} failed")?;
+    Ok(failures == 0)
+}
+
+/// Decodes RLP encoded headers and checks their hashes, writing one line per header to `out`.
+///
+/// Without `--hash` a header can only be decoded, not verified, so it is reported as `decoded`.
+///
+/// # Returns
+///
+/// `true` if every header decodes as a header of its era and matches its expected hash.
+fn verify_rlp<W: Write>(
+    headers: &[String],
+    hashes: &[String],
+    out: &mut W,
+) -> Result<bool, String> {
+    if !hashes.is_empty() && hashes.len() != headers.len() {
+        return Err(format!(
+            "expected {} hashes, got {}",
+            headers.len(),
+            hashes.len()
+        ));
+    }
+
+    let mut valid = true;
+    for (index, header) in headers.iter().enumerate() {
+        let encoded = hex::decode(header.strip_prefix("0x").unwrap_or(header))
+            .map_err(|error| format!("header {index}: {error}"))?;
+        let hash = keccak256(&encoded);
+        let number = rlp::Rlp::new(&encoded)
+            .val_at::<U256>(8)
+            .map_err(|error| format!("header {index}: {error}"))?;
+
+        let decoded = u64::try_from(number)
+            .ok()
+            .and_then(|number| decode_block_header(number, &encoded))
+            .is_some();
+        let expected = match hashes.get(index) {
+            Some(expected) => {
+                Some(H256::from_str(expected).map_err(|error| format!("hash {index}: {error}"))?)
+            }
+            None => None,
+        };
+
+        let status = match expected {
+            _ if !decoded => "not a header of its era",
+            Some(expected) if expected != hash => "hash mismatch",
+            Some(_) => "ok",
+            None => "decoded",
+        };
+        valid &= matches!(status, "ok" | "decoded");
+        writeln!(out, "{number} {hash:?} {status}").map_err(|error| error.to_string())?;
+    }
+    Ok(valid)
+}
+
+/// Maps the outcome of a command to the exit code of the binary: 0 if every block is valid,
+/// 1 if some block failed, and 2 if the input could not be read.
+fn exit_code(result: &Result<bool, String>) -> u8 {
+    match result {
+        Ok(true) => 0,
+        Ok(false) => 1,
+        Err(_) => 2,
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Headers {
+            path,
+            format,
+            chain,
+            quiet,
+        } => verify_headers(path, format, chain, quiet).map_err(|error| error.to_string()),
+        Command::Rlp { headers, hashes } => {
+            verify_rlp(&headers, &hashes, &mut std::io::stdout().lock())
+        }
+    };
+
+    if let Err(error) = &result {
+        eprintln!("error: {error}");
+    }
+    ExitCode::from(exit_code(&result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_rlp_types::BlockHeader;
+    use eth_rlp_verify::test_helpers::{create_test_chain, create_test_chain_genesis};
+
+    /// Mainnet block 1.
+    const BLOCK_1_RLP: &str = "f90211a0d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d493479405a56e2d52c817161883f50c441c3228cfe54d9fa0d67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b90100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008503ff80000001821388808455ba422499476574682f76312e302e302f6c696e75782f676f312e342e32a0969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f5988539bd4979fef1ec4";
+    const BLOCK_1_HASH: &str = "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6";
+
+    fn rlp(headers: &[&str], hashes: &[&str]) -> (Result<bool, String>, String) {
+        let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+        let hashes: Vec<String> = hashes.iter().map(|hash| hash.to_string()).collect();
+        let mut out = Vec::new();
+        let result = verify_rlp(&headers, &hashes, &mut out);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    fn headers(blocks: &[BlockHeader], chain: bool) -> (Result<bool, String>, String) {
+        let json = serde_json::to_vec(blocks).unwrap();
+        let mut out = Vec::new();
+        let result = check_headers(json.as_slice(), Format::Json, chain, false, &mut out)
+            .map_err(|error| error.to_string());
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_verify_rlp() {
+        let line = format!("1 {BLOCK_1_HASH}");
+
+        let (result, out) = rlp(&[BLOCK_1_RLP], &[]);
+        assert_eq!(out, format!("{line} decoded\n"));
+        assert_eq!(exit_code(&result), 0);
+
+        let (result, out) = rlp(&[&format!("0x{BLOCK_1_RLP}")], &[BLOCK_1_HASH]);
+        assert_eq!(out, format!("{line} ok\n"));
+        assert_eq!(exit_code(&result), 0);
+
+        let wrong = format!("{:?}", H256::zero());
+        let (result, out) = rlp(&[BLOCK_1_RLP], &[&wrong]);
+        assert_eq!(out, format!("{line} hash mismatch\n"));
+        assert_eq!(exit_code(&result), 1);
+
+        let (result, _) = rlp(&[BLOCK_1_RLP, BLOCK_1_RLP], &[BLOCK_1_HASH]);
+        assert_eq!(exit_code(&result), 2);
+        let (result, _) = rlp(&["zz"], &[]);
+        assert_eq!(exit_code(&result), 2);
+    }
+
+    #[test]
+    fn test_verify_headers() {
+        let mut blocks = create_test_chain(0, 3);
+        let (result, out) = headers(&blocks, true);
+        assert_eq!(exit_code(&result), 0);
+        assert!(out.ends_with("verified 3 headers, 0 failed\n"));
+
+        // A block whose parent hash is wrong still has a valid hash, so only --chain fails it.
+        blocks[2] = create_test_chain_genesis(&format!("{:?}", H256::zero()), 2, 1, 1).remove(0);
+        let (result, _) = headers(&blocks, false);
+        assert_eq!(exit_code(&result), 0);
+        let (result, out) = headers(&blocks, true);
+        assert_eq!(exit_code(&result), 1);
+        assert!(out.contains(&format!("2 {} broken link\n", blocks[2].block_hash)));
+
+        blocks[1].gas_used += 1;
+        let (result, out) = headers(&blocks, false);
+        assert_eq!(exit_code(&result), 1);
+        assert!(out.ends_with("verified 3 headers, 1 failed\n"));
+
+        let mut out = Vec::new();
+        let result = check_headers(&b"not json"[..], Format::Json, false, false, &mut out);
+        assert_eq!(exit_code(&result.map_err(|error| error.to_string())), 2);
+    }
+}
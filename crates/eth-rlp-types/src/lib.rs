@@ -1,5 +1,5 @@
-use primitive_types::H256;
 use fixed_hash::rustc_hex::FromHexError;
+use primitive_types::H256;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use thiserror::Error;
@@ -55,7 +55,9 @@ pub enum BlockHeaderError {
 /// - `blob_gas_used`: The amount of blob gas used, specific to blob transactions (optional).
 /// - `excess_blob_gas`: The excess blob gas present in the block (optional).
 /// - `parent_beacon_block_root`: The root of the parent beacon block, used in Ethereum's proof-of-stake chain (optional).
+/// - `requests_hash`: The commitment to the execution layer requests of the block, from Prague onwards (optional).
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
 pub struct BlockHeader {
     pub block_hash: String,               // character(66) NOT NULL
     pub number: i64,                      // bigint NOT NULL
@@ -80,6 +82,7 @@ pub struct BlockHeader {
     pub blob_gas_used: Option<String>,    // character varying(78)
     pub excess_blob_gas: Option<String>,  // character varying(78)
     pub parent_beacon_block_root: Option<String>, // character varying(66)
+    pub requests_hash: Option<String>,    // character varying(66)
 }
 
 /// A trait that defines common behaviors for Ethereum block headers, including RLP encoding and hash computation.
@@ -154,7 +157,7 @@ pub trait BlockHeaderTrait {
         let content = hex_str.strip_prefix("0x").unwrap_or(hex_str);
 
         // Pad with a leading '0' if the length is odd
-        let padded_content = if content.len() % 2 != 0 {
+        let padded_content = if !content.len().is_multiple_of(2) {
             format!("0{}", content)
         } else {
             content.to_string()
@@ -178,8 +181,10 @@ pub trait BlockHeaderTrait {
     }
 }
 
+#[cfg(test)]
 struct BlockHeaderImpl;
 
+#[cfg(test)]
 impl BlockHeaderTrait for BlockHeaderImpl {
     fn rlp_encode(&self) -> Vec<u8> {
         vec![]
@@ -0,0 +1,43 @@
+//! Compares sequential and parallel verification of a batch of headers.
+//!
+//! Run with `cargo bench -p eth-rlp-verify --features parallel`. The batch size defaults to
+//! 100,000 headers and can be set with the `BENCH_HEADERS` environment variable.
+
+use eth_rlp_verify::chain::{verify_blocks_parallel, ChainVerifier};
+use eth_rlp_verify::test_helpers::create_test_chain;
+use std::time::Instant;
+
+fn main() {
+    let count = std::env::var("BENCH_HEADERS")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(100_000);
+    let headers = create_test_chain(0, count);
+
+    let start = Instant::now();
+    let sequential = ChainVerifier::new()
+        .verify_iter(headers.iter().cloned())
+        .filter(|result| result.is_valid())
+        .count();
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let parallel = verify_blocks_parallel(&headers)
+        .iter()
+        .filter(|result| result.is_valid())
+        .count();
+    let parallel_time = start.elapsed();
+
+    assert_eq!(sequential, parallel);
+    println!("verified {count} headers");
+    println!(
+        "sequential: {:?} ({:.0} headers/s)",
+        sequential_time,
+        count as f64 / sequential_time.as_secs_f64()
+    );
+    println!(
+        "parallel:   {:?} ({:.0} headers/s)",
+        parallel_time,
+        count as f64 / parallel_time.as_secs_f64()
+    );
+}
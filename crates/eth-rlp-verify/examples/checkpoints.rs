@@ -0,0 +1,41 @@
+//! Prints the mainnet checkpoint table, one `number,hash` line every `CHECKPOINT_INTERVAL` blocks,
+//! from a trusted node.
+//!
+//! ```text
+//! cargo run --example checkpoints --features rpc -- <node url> <last block> > checkpoints/mainnet.csv
+//! ```
+//!
+//! Every header returned by the node is checked against its hash before being written.
+
+use eth_rlp_verify::rpc::RpcClient;
+use std::process::ExitCode;
+
+/// The spacing of the entries of the table, in blocks.
+const CHECKPOINT_INTERVAL: u64 = 100_000;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let (Some(url), Some(Ok(last))) = (args.next(), args.next().map(|last| last.parse::<u64>()))
+    else {
+        eprintln!("usage: checkpoints <node url> <last block>");
+        return ExitCode::from(2);
+    };
+    let client = RpcClient::new(&url);
+
+    println!("# number,hash");
+    for number in (0..=last).step_by(CHECKPOINT_INTERVAL as usize) {
+        let block = match client.fetch_blocks(number..=number) {
+            Ok(blocks) => blocks.into_iter().next(),
+            Err(error) => {
+                eprintln!("block {number}: {error}");
+                return ExitCode::FAILURE;
+            }
+        };
+        let Some(block) = block.filter(|block| block.verify_hash().unwrap_or_default()) else {
+            eprintln!("block {number}: header does not match its hash");
+            return ExitCode::FAILURE;
+        };
+        println!("{number},{}", block.hash);
+    }
+    ExitCode::SUCCESS
+}
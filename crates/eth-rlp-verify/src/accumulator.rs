@@ -0,0 +1,481 @@
+use crate::constants::LONDON_END;
+use crate::eras::{BlockHeaderGenesis, BlockHeaderLondon};
+use crate::ssz::{
+    hash_pair, is_valid_merkle_branch, merkle_branch, merkleize, mix_in_length, uint256_chunk,
+    uint64_chunk,
+};
+use eth_rlp_types::BlockHeaderTrait;
+use primitive_types::{H256, U256};
+use thiserror::Error;
+
+/// The number of headers in an epoch of the pre-merge accumulator.
+pub const EPOCH_SIZE: u64 = 8192;
+
+/// The depth of the tree holding the header records of an epoch, `log2(EPOCH_SIZE)`.
+const EPOCH_DEPTH: usize = 13;
+
+/// The depth of the tree holding the epoch roots, `log2(MAX_HISTORICAL_EPOCHS)` with
+/// `MAX_HISTORICAL_EPOCHS = 131072`.
+const HISTORICAL_EPOCHS_DEPTH: usize = 17;
+
+/// The length of a header proof: the total difficulty chunk, the epoch tree branch and the list
+/// length mix-in.
+pub const HEADER_PROOF_LENGTH: usize = EPOCH_DEPTH + 2;
+
+/// The `hash_tree_root` of the mainnet pre-merge accumulator, as used by the Portal network.
+///
+/// The accumulator holds the roots of the 1897 epochs covering blocks `0` to `LONDON_END`, the
+/// last block before the merge.
+pub const PRE_MERGE_ACCUMULATOR_ROOT: H256 = H256([
+    0x8e, 0xac, 0x39, 0x9e, 0x24, 0x48, 0x0d, 0xce, 0x3c, 0xfe, 0x06, 0xf4, 0xbd, 0xec, 0xba, 0x51,
+    0xc6, 0xe5, 0xd0, 0xc4, 0x62, 0x00, 0xe3, 0xe8, 0x61, 0x1a, 0x0b, 0x44, 0xa3, 0xa6, 0x9f, 0xf9,
+]);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AccumulatorError {
+    #[error("Block {0} is not the first block of an epoch")]
+    NotEpochStart(u64),
+    #[error("Block {0} is after the merge")]
+    PostMergeBlock(u64),
+    #[error("An epoch holds at most {EPOCH_SIZE} headers, got {0}")]
+    EpochTooLarge(usize),
+    #[error("Expected block {expected}, got block {got}")]
+    NonConsecutiveBlock { expected: u64, got: u64 },
+    #[error("Parent hash of block {0} does not match the previous header")]
+    ParentHashMismatch(u64),
+    #[error("Block {0} is not part of this epoch")]
+    BlockNotInEpoch(u64),
+    #[error("Epoch {0} is not part of the accumulator")]
+    EpochNotFound(u64),
+    #[error("Invalid SSZ encoding: {0}")]
+    InvalidSsz(&'static str),
+}
+
+/// A pre-merge block header that can be recorded in an epoch accumulator.
+///
+/// This is implemented for the header types of the eras preceding the merge, which carry the
+/// proof-of-work difficulty needed to compute the total difficulty of the chain.
+pub trait PreMergeBlockHeader: BlockHeaderTrait {
+    fn block_number(&self) -> u64;
+    fn difficulty(&self) -> U256;
+    fn parent_hash(&self) -> H256;
+}
+
+impl PreMergeBlockHeader for BlockHeaderGenesis {
+    fn block_number(&self) -> u64 {
+        self.number.as_u64()
+    }
+
+    fn difficulty(&self) -> U256 {
+        self.difficulty
+    }
+
+    fn parent_hash(&self) -> H256 {
+        self.parent_hash
+    }
+}
+
+impl PreMergeBlockHeader for BlockHeaderLondon {
+    fn block_number(&self) -> u64 {
+        self.number.as_u64()
+    }
+
+    fn difficulty(&self) -> U256 {
+        self.difficulty
+    }
+
+    fn parent_hash(&self) -> H256 {
+        self.parent_hash
+    }
+}
+
+/// The record of a single header in an epoch accumulator.
+///
+/// # Fields
+///
+/// - `block_hash`: The hash of the block.
+/// - `total_difficulty`: The total difficulty of the chain up to and including the block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeaderRecord {
+    pub block_hash: H256,
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Computes the SSZ `hash_tree_root` of the record.
+    pub fn tree_hash_root(&self) -> H256 {
+        hash_pair(&self.block_hash, &uint256_chunk(self.total_difficulty))
+    }
+}
+
+/// The accumulator of a single epoch: the records of up to `EPOCH_SIZE` consecutive headers,
+/// starting at a block number that is a multiple of `EPOCH_SIZE`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EpochAccumulator {
+    pub records: Vec<HeaderRecord>,
+}
+
+impl EpochAccumulator {
+    /// Builds an epoch accumulator from a sequence of pre-merge headers.
+    ///
+    /// The headers must start at an epoch boundary, be consecutive and link to each other through
+    /// their parent hashes. The hash of each header is computed from its RLP encoding.
+    ///
+    /// # Arguments
+    ///
+    /// - `headers`: The headers of the epoch, in ascending block order.
+    /// - `parent_total_difficulty`: The total difficulty of the chain up to the block preceding
+    ///   the epoch, or zero for the first epoch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<EpochAccumulator, AccumulatorError>` containing the epoch accumulator.
+    pub fn from_headers<T: PreMergeBlockHeader>(
+        headers: &[T],
+        parent_total_difficulty: U256,
+    ) -> Result<Self, AccumulatorError> {
+        if headers.len() > EPOCH_SIZE as usize {
+            return Err(AccumulatorError::EpochTooLarge(headers.len()));
+        }
+        let Some(first) = headers.first() else {
+            return Ok(Self::default());
+        };
+        if !first.block_number().is_multiple_of(EPOCH_SIZE) {
+            return Err(AccumulatorError::NotEpochStart(first.block_number()));
+        }
+
+        let mut records: Vec<HeaderRecord> = Vec::with_capacity(headers.len());
+        let mut total_difficulty = parent_total_difficulty;
+        for (offset, header) in headers.iter().enumerate() {
+            let number = header.block_number();
+            let expected = first.block_number() + offset as u64;
+            if number != expected {
+                return Err(AccumulatorError::NonConsecutiveBlock {
+                    expected,
+                    got: number,
+                });
+            }
+            if number > LONDON_END {
+                return Err(AccumulatorError::PostMergeBlock(number));
+            }
+            if let Some(previous) = records.last() {
+                if header.parent_hash() != previous.block_hash {
+                    return Err(AccumulatorError::ParentHashMismatch(number));
+                }
+            }
+
+            total_difficulty += header.difficulty();
+            records.push(HeaderRecord {
+                block_hash: header.compute_hash(),
+                total_difficulty,
+            });
+        }
+
+        Ok(EpochAccumulator { records })
+    }
+
+    /// Computes the SSZ `hash_tree_root` of the epoch, as stored in the pre-merge accumulator.
+    pub fn tree_hash_root(&self) -> H256 {
+        let roots: Vec<H256> = self
+            .records
+            .iter()
+            .map(HeaderRecord::tree_hash_root)
+            .collect();
+        mix_in_length(merkleize(&roots, EPOCH_DEPTH), self.records.len())
+    }
+
+    /// Generates the proof that the header of `block_number` is included in this epoch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<H256>, AccumulatorError>` containing the `HEADER_PROOF_LENGTH` proof chunks,
+    /// which can be checked with `verify_header_proof`.
+    pub fn header_proof(&self, block_number: u64) -> Result<Vec<H256>, AccumulatorError> {
+        let index = (block_number % EPOCH_SIZE) as usize;
+        let record = self
+            .records
+            .get(index)
+            .ok_or(AccumulatorError::BlockNotInEpoch(block_number))?;
+
+        let roots: Vec<H256> = self
+            .records
+            .iter()
+            .map(HeaderRecord::tree_hash_root)
+            .collect();
+        let mut proof = Vec::with_capacity(HEADER_PROOF_LENGTH);
+        proof.push(uint256_chunk(record.total_difficulty));
+        proof.extend(merkle_branch(&roots, EPOCH_DEPTH, index));
+        proof.push(uint64_chunk(self.records.len() as u64));
+        Ok(proof)
+    }
+}
+
+/// Verifies that a block hash is included in the epoch with the given root.
+///
+/// The proof is the SSZ Merkle branch from the `block_hash` field of the header record up to the
+/// epoch root, as used by the Portal network for pre-merge headers.
+///
+/// # Arguments
+///
+/// - `epoch_root`: The trusted `hash_tree_root` of the epoch.
+/// - `block_hash`: The hash of the block being proven.
+/// - `block_number`: The number of the block being proven.
+/// - `proof`: The `HEADER_PROOF_LENGTH` proof chunks.
+///
+/// # Returns
+///
+/// A `bool` indicating whether the proof is valid.
+pub fn verify_header_proof(
+    epoch_root: H256,
+    block_hash: H256,
+    block_number: u64,
+    proof: &[H256],
+) -> bool {
+    // The block hash is the first field of its record, itself the `index`th element of the list.
+    let index = (block_number % EPOCH_SIZE) * 2;
+    proof.len() == HEADER_PROOF_LENGTH
+        && is_valid_merkle_branch(block_hash, proof, HEADER_PROOF_LENGTH, index, epoch_root)
+}
+
+/// The pre-merge (master) accumulator, committing to the roots of every pre-merge epoch.
+///
+/// Once the accumulator has been checked against `PRE_MERGE_ACCUMULATOR_ROOT` with `verify_root`,
+/// its epoch roots can be trusted to prove that pre-merge headers are canonical.
+///
+/// # Fields
+///
+/// - `historical_epochs`: The roots of the epochs, in order.
+/// - `current_epoch`: The records of the epoch being built, empty once the merge happened.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreMergeAccumulator {
+    pub historical_epochs: Vec<H256>,
+    pub current_epoch: EpochAccumulator,
+}
+
+impl PreMergeAccumulator {
+    /// Decodes an accumulator from its SSZ encoding, e.g. the Portal network `pre_merge_acc.ssz`.
+    pub fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, AccumulatorError> {
+        let read_offset = |position: usize| -> Result<usize, AccumulatorError> {
+            let offset = bytes
+                .get(position..position + 4)
+                .ok_or(AccumulatorError::InvalidSsz("missing offset"))?;
+            Ok(u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize)
+        };
+
+        let epochs_offset = read_offset(0)?;
+        let current_offset = read_offset(4)?;
+        if epochs_offset != 8 || current_offset < epochs_offset || current_offset > bytes.len() {
+            return Err(AccumulatorError::InvalidSsz("invalid offsets"));
+        }
+
+        let epochs = &bytes[epochs_offset..current_offset];
+        let current = &bytes[current_offset..];
+        if !epochs.len().is_multiple_of(32) || !current.len().is_multiple_of(64) {
+            return Err(AccumulatorError::InvalidSsz("invalid list length"));
+        }
+
+        Ok(PreMergeAccumulator {
+            historical_epochs: epochs.chunks(32).map(H256::from_slice).collect(),
+            current_epoch: EpochAccumulator {
+                records: current
+                    .chunks(64)
+                    .map(|record| HeaderRecord {
+                        block_hash: H256::from_slice(&record[..32]),
+                        total_difficulty: U256::from_little_endian(&record[32..]),
+                    })
+                    .collect(),
+            },
+        })
+    }
+
+    /// Computes the SSZ `hash_tree_root` of the accumulator.
+    pub fn tree_hash_root(&self) -> H256 {
+        let epochs_root = mix_in_length(
+            merkleize(&self.historical_epochs, HISTORICAL_EPOCHS_DEPTH),
+            self.historical_epochs.len(),
+        );
+        hash_pair(&epochs_root, &self.current_epoch.tree_hash_root())
+    }
+
+    /// Checks the accumulator against the known mainnet `PRE_MERGE_ACCUMULATOR_ROOT`.
+    pub fn verify_root(&self) -> bool {
+        self.tree_hash_root() == PRE_MERGE_ACCUMULATOR_ROOT
+    }
+
+    /// Returns the root of the epoch containing `block_number`.
+    pub fn epoch_root(&self, block_number: u64) -> Result<H256, AccumulatorError> {
+        if block_number > LONDON_END {
+            return Err(AccumulatorError::PostMergeBlock(block_number));
+        }
+        let epoch = block_number / EPOCH_SIZE;
+        self.historical_epochs
+            .get(epoch as usize)
+            .copied()
+            .ok_or(AccumulatorError::EpochNotFound(epoch))
+    }
+
+    /// Checks that an epoch accumulator built from headers matches the recorded epoch root.
+    ///
+    /// # Arguments
+    ///
+    /// - `epoch`: The index of the epoch, i.e. the number of its first block divided by `EPOCH_SIZE`.
+    /// - `epoch_accumulator`: The epoch accumulator, e.g. built with `EpochAccumulator::from_headers`.
+    pub fn verify_epoch(
+        &self,
+        epoch: u64,
+        epoch_accumulator: &EpochAccumulator,
+    ) -> Result<bool, AccumulatorError> {
+        let root = self.epoch_root(epoch * EPOCH_SIZE)?;
+        Ok(root == epoch_accumulator.tree_hash_root())
+    }
+
+    /// Verifies that a block hash is canonical using a header proof against its epoch root.
+    ///
+    /// # Arguments
+    ///
+    /// - `block_hash`: The hash of the block being proven.
+    /// - `block_number`: The number of the block being proven.
+    /// - `proof`: The `HEADER_PROOF_LENGTH` proof chunks.
+    pub fn verify_header(
+        &self,
+        block_hash: H256,
+        block_number: u64,
+        proof: &[H256],
+    ) -> Result<bool, AccumulatorError> {
+        let root = self.epoch_root(block_number)?;
+        Ok(verify_header_proof(root, block_hash, block_number, proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitive_types::H160;
+
+    fn mock_chain(length: u64) -> Vec<BlockHeaderGenesis> {
+        let mut headers: Vec<BlockHeaderGenesis> = vec![];
+        for number in 0..length {
+            let parent_hash = headers
+                .last()
+                .map(|parent| parent.compute_hash())
+                .unwrap_or_default();
+            headers.push(BlockHeaderGenesis {
+                parent_hash,
+                ommers_hash: H256::zero(),
+                beneficiary: H160::zero(),
+                state_root: H256::zero(),
+                transactions_root: H256::zero(),
+                receipts_root: H256::zero(),
+                logs_bloom: [0; 256],
+                difficulty: U256::from(1000 + number),
+                number: U256::from(number),
+                gas_limit: U256::zero(),
+                gas_used: U256::zero(),
+                timestamp: U256::from(number),
+                extra_data: vec![],
+                mix_hash: H256::zero(),
+                nonce: [0; 8],
+            });
+        }
+        headers
+    }
+
+    #[test]
+    fn test_epoch_accumulator_proofs() {
+        let headers = mock_chain(100);
+        let epoch = EpochAccumulator::from_headers(&headers, U256::zero()).unwrap();
+        assert_eq!(epoch.records[1].total_difficulty, U256::from(2001));
+
+        let accumulator = PreMergeAccumulator {
+            historical_epochs: vec![epoch.tree_hash_root()],
+            current_epoch: EpochAccumulator::default(),
+        };
+        assert_eq!(accumulator.verify_epoch(0, &epoch), Ok(true));
+        assert!(!accumulator.verify_root());
+
+        for number in [0, 1, 42, 99] {
+            let proof = epoch.header_proof(number).unwrap();
+            let hash = headers[number as usize].compute_hash();
+            assert_eq!(accumulator.verify_header(hash, number, &proof), Ok(true));
+            assert_eq!(
+                accumulator.verify_header(hash, number + 1, &proof),
+                Ok(false)
+            );
+        }
+        assert_eq!(
+            accumulator.verify_header(H256::zero(), EPOCH_SIZE, &[]),
+            Err(AccumulatorError::EpochNotFound(1))
+        );
+    }
+
+    #[test]
+    fn test_epoch_accumulator_from_mainnet_genesis() {
+        let genesis = BlockHeaderGenesis {
+            parent_hash: H256::zero(),
+            ommers_hash: H256::from_slice(
+                &hex::decode("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347")
+                    .unwrap(),
+            ),
+            beneficiary: H160::zero(),
+            state_root: H256::from_slice(
+                &hex::decode("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544")
+                    .unwrap(),
+            ),
+            transactions_root: crate::proofs::EMPTY_TRIE_ROOT,
+            receipts_root: crate::proofs::EMPTY_TRIE_ROOT,
+            logs_bloom: [0; 256],
+            difficulty: U256::from(0x400000000u64),
+            number: U256::zero(),
+            gas_limit: U256::from(5000),
+            gas_used: U256::zero(),
+            timestamp: U256::zero(),
+            extra_data: hex::decode(
+                "11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+            )
+            .unwrap(),
+            mix_hash: H256::zero(),
+            nonce: [0, 0, 0, 0, 0, 0, 0, 0x42],
+        };
+
+        let epoch = EpochAccumulator::from_headers(&[genesis], U256::zero()).unwrap();
+        assert_eq!(
+            epoch.records[0].block_hash,
+            H256::from_slice(
+                &hex::decode("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3")
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            epoch.records[0].total_difficulty,
+            U256::from(0x400000000u64)
+        );
+    }
+
+    #[test]
+    fn test_epoch_accumulator_rejects_broken_chain() {
+        let mut headers = mock_chain(10);
+        headers[5].parent_hash = H256::zero();
+        assert_eq!(
+            EpochAccumulator::from_headers(&headers, U256::zero()),
+            Err(AccumulatorError::ParentHashMismatch(5))
+        );
+        assert_eq!(
+            EpochAccumulator::from_headers(&headers[1..], U256::zero()),
+            Err(AccumulatorError::NotEpochStart(1))
+        );
+    }
+
+    #[test]
+    fn test_pre_merge_accumulator_ssz_decoding() {
+        let mut bytes = vec![8, 0, 0, 0, 72, 0, 0, 0];
+        bytes.extend([0x11; 32]);
+        bytes.extend([0x22; 32]);
+        let accumulator = PreMergeAccumulator::from_ssz_bytes(&bytes).unwrap();
+        assert_eq!(
+            accumulator.historical_epochs,
+            vec![H256::repeat_byte(0x11), H256::repeat_byte(0x22)]
+        );
+        assert!(accumulator.current_epoch.records.is_empty());
+        assert!(PreMergeAccumulator::from_ssz_bytes(&bytes[..70]).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::chain::{BlockResult, ChainVerifier};
+use crate::{decode_block_header, encode_block_header};
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The magic bytes opening every archive.
+pub const MAGIC: [u8; 4] = *b"EHA1";
+
+/// The length of the archive header: the magic bytes and the first block number.
+const HEADER_LENGTH: u64 = 12;
+
+/// The length of the archive footer: the offset of the index and the number of blocks.
+const FOOTER_LENGTH: u64 = 16;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Not a header archive")]
+    InvalidMagic,
+    #[error("Invalid archive index")]
+    InvalidIndex,
+    #[error("Expected block {expected}, got block {got}")]
+    NonConsecutiveBlock { expected: u64, got: u64 },
+    #[error("Block {number} has an invalid hash")]
+    InvalidHash { number: u64 },
+    #[error("Header of block {number} cannot be encoded")]
+    UnencodableHeader { number: u64 },
+    #[error("Header of block {number} cannot be decoded")]
+    UndecodableHeader { number: u64 },
+    #[error("Block {number} is not in the archive")]
+    BlockNotFound { number: u64 },
+}
+
+/// Writes a compact archive of consecutive headers.
+///
+/// Each block is stored as its 32-byte hash followed by the output of `encode_block_header`, and
+/// `finish` appends an index of the record offsets, so that the archive can be read in any
+/// order. The layout is:
+///
+/// - the magic bytes `EHA1` and the first block number, as a little-endian `u64`;
+/// - one record per block;
+/// - the index, one little-endian `u64` offset per record plus the end offset of the last one;
+/// - the offset of the index and the number of blocks, as little-endian `u64`s.
+pub struct ArchiveWriter<W> {
+    writer: W,
+    next_block: u64,
+    offsets: Vec<u64>,
+    position: u64,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Creates an archive starting at `first_block`.
+    pub fn new(mut writer: W, first_block: u64) -> Result<Self, ArchiveError> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&first_block.to_le_bytes())?;
+        Ok(ArchiveWriter {
+            writer,
+            next_block: first_block,
+            offsets: vec![],
+            position: HEADER_LENGTH,
+        })
+    }
+
+    /// Appends the next header.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), ArchiveError>`, failing if the header does not follow the previous one or
+    /// cannot be encoded. The archive is left unchanged on error.
+    pub fn append(&mut self, block_header: &VerifiableBlockHeader) -> Result<(), ArchiveError> {
+        let number = block_header.number as u64;
+        if number != self.next_block {
+            return Err(ArchiveError::NonConsecutiveBlock {
+                expected: self.next_block,
+                got: number,
+            });
+        }
+        let hash = H256::from_str(&block_header.block_hash)
+            .map_err(|_| ArchiveError::InvalidHash { number })?;
+        let encoded = encode_block_header(number, block_header.clone())
+            .ok_or(ArchiveError::UnencodableHeader { number })?;
+
+        self.writer.write_all(hash.as_bytes())?;
+        self.writer.write_all(&encoded)?;
+        self.offsets.push(self.position);
+        self.position += (hash.as_bytes().len() + encoded.len()) as u64;
+        self.next_block += 1;
+        Ok(())
+    }
+
+    /// Writes the index and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, ArchiveError> {
+        let index_offset = self.position;
+        for offset in self.offsets.iter().chain(std::iter::once(&index_offset)) {
+            self.writer.write_all(&offset.to_le_bytes())?;
+        }
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer
+            .write_all(&(self.offsets.len() as u64).to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads an archive written by `ArchiveWriter`, in any order.
+///
+/// # Fields
+///
+/// - `reader`: The archive.
+/// - `first_block`: The number of the first block.
+/// - `offsets`: The offset of every record, followed by the end offset of the last one.
+pub struct ArchiveReader<R> {
+    reader: R,
+    first_block: u64,
+    offsets: Vec<u64>,
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, ArchiveError> {
+    let mut word = [0u8; 8];
+    reader.read_exact(&mut word)?;
+    Ok(u64::from_le_bytes(word))
+}
+
+impl<R: Read + Seek> ArchiveReader<R> {
+    /// Opens an archive, reading its index.
+    pub fn open(mut reader: R) -> Result<Self, ArchiveError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(ArchiveError::InvalidMagic);
+        }
+        let first_block = read_u64(&mut reader)?;
+
+        let length = reader.seek(SeekFrom::End(0))?;
+        if length < HEADER_LENGTH + FOOTER_LENGTH {
+            return Err(ArchiveError::InvalidIndex);
+        }
+        reader.seek(SeekFrom::Start(length - FOOTER_LENGTH))?;
+        let index_offset = read_u64(&mut reader)?;
+        let block_count = read_u64(&mut reader)?;
+        if block_count
+            .checked_add(1)
+            .and_then(|entries| entries.checked_mul(8))
+            .and_then(|index_length| index_offset.checked_add(index_length))
+            != Some(length - FOOTER_LENGTH)
+        {
+            return Err(ArchiveError::InvalidIndex);
+        }
+
+        reader.seek(SeekFrom::Start(index_offset))?;
+        let offsets = (0..=block_count)
+            .map(|_| read_u64(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Records start right after the header, each one holding at least its hash.
+        if offsets.first() != Some(&HEADER_LENGTH)
+            || offsets
+                .windows(2)
+                .any(|window| window[1] < window[0].saturating_add(32))
+            || offsets.last() != Some(&index_offset)
+        {
+            return Err(ArchiveError::InvalidIndex);
+        }
+
+        Ok(ArchiveReader {
+            reader,
+            first_block,
+            offsets,
+        })
+    }
+
+    /// Returns the numbers of the blocks in the archive.
+    pub fn blocks(&self) -> Range<u64> {
+        self.first_block..self.first_block + (self.offsets.len() - 1) as u64
+    }
+
+    /// Reads the stored hash and the RLP encoded header of a block.
+    pub fn read_raw(&mut self, number: u64) -> Result<(H256, Vec<u8>), ArchiveError> {
+        if !self.blocks().contains(&number) {
+            return Err(ArchiveError::BlockNotFound { number });
+        }
+        let position = (number - self.first_block) as usize;
+        let (start, end) = (self.offsets[position], self.offsets[position + 1]);
+        let mut record = vec![0; (end - start) as usize];
+        self.reader.seek(SeekFrom::Start(start))?;
+        self.reader.read_exact(&mut record)?;
+        let encoded = record.split_off(32);
+        Ok((H256::from_slice(&record), encoded))
+    }
+
+    /// Reads and decodes the header of a block, using the stored hash as block hash.
+    pub fn header(&mut self, number: u64) -> Result<VerifiableBlockHeader, ArchiveError> {
+        let (hash, encoded) = self.read_raw(number)?;
+        let mut block_header = decode_block_header(number, &encoded)
+            .ok_or(ArchiveError::UndecodableHeader { number })?;
+        block_header.block_hash = format!("{hash:?}");
+        Ok(block_header)
+    }
+
+    /// Reads the headers of a range of blocks, in ascending order.
+    pub fn headers(
+        &mut self,
+        range: RangeInclusive<u64>,
+    ) -> impl Iterator<Item = Result<VerifiableBlockHeader, ArchiveError>> + '_ {
+        range.map(move |number| self.header(number))
+    }
+
+    /// Verifies a range of blocks, checking every stored hash and every parent link.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<BlockResult>, ArchiveError>` with one result per block, or an error if a
+    /// block cannot be read or decoded.
+    pub fn verify_range(
+        &mut self,
+        range: RangeInclusive<u64>,
+    ) -> Result<Vec<BlockResult>, ArchiveError> {
+        let mut verifier = ChainVerifier::new();
+        self.headers(range)
+            .map(|block_header| Ok(verifier.push(block_header?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_chain;
+    use std::io::Cursor;
+
+    fn archive(chain: &[VerifiableBlockHeader]) -> Vec<u8> {
+        let mut writer = ArchiveWriter::new(vec![], chain[0].number as u64).unwrap();
+        for block_header in chain {
+            writer.append(block_header).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let chain = create_test_chain(0, 12);
+        let mut reader = ArchiveReader::open(Cursor::new(archive(&chain[2..]))).unwrap();
+        assert_eq!(reader.blocks(), 2..12);
+
+        let expected = |number: usize| VerifiableBlockHeader {
+            ommers_hash: chain[number].sha3_uncles.clone(),
+            ..chain[number].clone()
+        };
+        assert_eq!(reader.header(9).unwrap(), expected(9));
+        assert_eq!(reader.header(2).unwrap(), expected(2));
+        let headers: Vec<VerifiableBlockHeader> =
+            reader.headers(4..=6).collect::<Result<_, _>>().unwrap();
+        assert_eq!(headers, (4..=6).map(expected).collect::<Vec<_>>());
+        assert!(matches!(
+            reader.header(12),
+            Err(ArchiveError::BlockNotFound { number: 12 })
+        ));
+
+        let results = reader.verify_range(2..=11).unwrap();
+        assert!(results.iter().all(BlockResult::is_valid));
+    }
+
+    #[test]
+    fn test_archive_errors() {
+        let mut chain = create_test_chain(0, 6);
+        let mut writer = ArchiveWriter::new(vec![], 0).unwrap();
+        assert!(matches!(
+            writer.append(&chain[1]),
+            Err(ArchiveError::NonConsecutiveBlock {
+                expected: 0,
+                got: 1
+            })
+        ));
+
+        chain[3].gas_used += 1;
+        let bytes = archive(&chain);
+        let failures: Vec<u64> = ArchiveReader::open(Cursor::new(bytes.clone()))
+            .unwrap()
+            .verify_range(0..=5)
+            .unwrap()
+            .into_iter()
+            .filter(|result| !result.is_valid())
+            .map(|result| result.number)
+            .collect();
+        assert_eq!(failures, vec![3]);
+
+        assert!(matches!(
+            ArchiveReader::open(Cursor::new(bytes[..bytes.len() - 1].to_vec())),
+            Err(ArchiveError::InvalidIndex)
+        ));
+        assert!(matches!(
+            ArchiveReader::open(Cursor::new(b"not an archive".to_vec())),
+            Err(ArchiveError::InvalidMagic)
+        ));
+    }
+}
@@ -0,0 +1,334 @@
+use crate::verify_block;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::U256;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+/// How the values of a field are normalized before being compared across providers.
+#[derive(Clone, Copy)]
+enum FieldKind {
+    /// A hex string compared case-insensitively, e.g. a hash or an address.
+    Data,
+    /// A quantity compared by value, so that `0x0a` and `0xa` agree.
+    Quantity,
+}
+
+/// Lists the header fields compared across providers, with their values.
+fn fields(h: &VerifiableBlockHeader) -> [(&'static str, FieldKind, Option<String>); 24] {
+    use FieldKind::{Data, Quantity};
+    [
+        ("block_hash", Data, Some(h.block_hash.clone())),
+        ("number", Quantity, Some(h.number.to_string())),
+        ("gas_limit", Quantity, Some(h.gas_limit.to_string())),
+        ("gas_used", Quantity, Some(h.gas_used.to_string())),
+        ("nonce", Data, Some(h.nonce.clone())),
+        ("transaction_root", Data, h.transaction_root.clone()),
+        ("receipts_root", Data, h.receipts_root.clone()),
+        ("state_root", Data, h.state_root.clone()),
+        ("base_fee_per_gas", Quantity, h.base_fee_per_gas.clone()),
+        ("parent_hash", Data, h.parent_hash.clone()),
+        ("ommers_hash", Data, h.ommers_hash.clone()),
+        ("miner", Data, h.miner.clone()),
+        ("logs_bloom", Data, h.logs_bloom.clone()),
+        ("difficulty", Quantity, h.difficulty.clone()),
+        ("totaldifficulty", Quantity, h.totaldifficulty.clone()),
+        ("sha3_uncles", Data, h.sha3_uncles.clone()),
+        ("timestamp", Quantity, h.timestamp.clone()),
+        ("extra_data", Data, h.extra_data.clone()),
+        ("mix_hash", Data, h.mix_hash.clone()),
+        ("withdrawals_root", Data, h.withdrawals_root.clone()),
+        ("blob_gas_used", Quantity, h.blob_gas_used.clone()),
+        ("excess_blob_gas", Quantity, h.excess_blob_gas.clone()),
+        (
+            "parent_beacon_block_root",
+            Data,
+            h.parent_beacon_block_root.clone(),
+        ),
+        ("requests_hash", Data, h.requests_hash.clone()),
+    ]
+}
+
+impl FieldKind {
+    /// Normalizes a value, keeping it as is if it cannot be parsed.
+    fn normalize(self, value: String) -> String {
+        match self {
+            FieldKind::Data => value.to_lowercase(),
+            FieldKind::Quantity => {
+                let parsed = match value.strip_prefix("0x") {
+                    Some(_) => U256::from_str(&value).ok(),
+                    None => U256::from_dec_str(&value).ok(),
+                };
+                parsed.map_or(value, |parsed| format!("{parsed:#x}"))
+            }
+        }
+    }
+}
+
+/// A field of a block on which providers disagree.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `field`: The name of the field, as in the `BlockHeader` table.
+/// - `values`: The normalized value served by each provider holding the block.
+/// - `expected`: The value agreed on by the providers, or `None` if they are evenly split.
+/// - `dissenters`: The providers serving a value other than `expected`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Discrepancy {
+    pub number: u64,
+    pub field: &'static str,
+    pub values: BTreeMap<String, Option<String>>,
+    pub expected: Option<Option<String>>,
+    pub dissenters: Vec<String>,
+}
+
+/// A header failing hash verification.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `provider`: The provider serving the header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidHeader {
+    pub number: u64,
+    pub provider: String,
+}
+
+/// A block served by some providers only.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `providers`: The providers not serving the block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MissingHeader {
+    pub number: u64,
+    pub providers: Vec<String>,
+}
+
+/// The outcome of comparing the headers of several providers.
+///
+/// # Fields
+///
+/// - `block_count`: The number of distinct block numbers served by any provider.
+/// - `invalid`: The headers failing hash verification.
+/// - `missing`: The blocks not served by every provider.
+/// - `discrepancies`: The fields on which providers disagree, by block number and field order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AuditReport {
+    pub block_count: u64,
+    pub invalid: Vec<InvalidHeader>,
+    pub missing: Vec<MissingHeader>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl AuditReport {
+    /// Returns whether every provider served the same valid headers.
+    pub fn is_consistent(&self) -> bool {
+        self.invalid.is_empty() && self.missing.is_empty() && self.discrepancies.is_empty()
+    }
+
+    /// Counts the discrepancies in which each provider was a dissenter.
+    pub fn dissent_counts(&self) -> BTreeMap<String, u64> {
+        let mut counts = BTreeMap::new();
+        for provider in self
+            .discrepancies
+            .iter()
+            .flat_map(|discrepancy| &discrepancy.dissenters)
+        {
+            *counts.entry(provider.clone()).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Selects the value most providers agree on, or `None` if several values tie.
+///
+/// Providers whose header verifies are counted first, so that a single honest provider
+/// outweighs any number of providers serving headers that do not hash to their claimed hash.
+fn expected_value(
+    values: &BTreeMap<String, Option<String>>,
+    valid: &BTreeSet<&str>,
+) -> Option<Option<String>> {
+    let mut counts: HashMap<&Option<String>, (usize, usize)> = HashMap::new();
+    for (provider, value) in values {
+        let count = counts.entry(value).or_default();
+        count.0 += usize::from(valid.contains(provider.as_str()));
+        count.1 += 1;
+    }
+
+    let best = counts.values().max()?;
+    let mut best_values = counts.iter().filter(|(_, count)| *count == best);
+    match (best_values.next(), best_values.next()) {
+        (Some((value, _)), None) => Some((*value).clone()),
+        _ => None,
+    }
+}
+
+/// Compares the headers served by several providers for the same range.
+///
+/// Every header is verified with `verify_block`, then every field of every block is compared
+/// across the providers serving it. Hex values are compared case-insensitively and quantities
+/// by value, so that formatting differences between providers are not reported.
+///
+/// # Arguments
+///
+/// - `sources`: The name of each provider and the headers it served, in any order.
+///
+/// # Returns
+///
+/// The `AuditReport` of the providers.
+pub fn audit_providers(sources: &[(&str, &[VerifiableBlockHeader])]) -> AuditReport {
+    let mut blocks: BTreeMap<u64, Vec<(&str, &VerifiableBlockHeader)>> = BTreeMap::new();
+    for (provider, block_headers) in sources {
+        for block_header in *block_headers {
+            blocks
+                .entry(block_header.number as u64)
+                .or_default()
+                .push((provider, block_header));
+        }
+    }
+
+    let mut report = AuditReport {
+        block_count: blocks.len() as u64,
+        ..AuditReport::default()
+    };
+    for (number, block_headers) in blocks {
+        let mut valid = BTreeSet::new();
+        for (provider, block_header) in &block_headers {
+            if verify_block(number, (*block_header).clone(), &block_header.block_hash)
+                .unwrap_or_default()
+            {
+                valid.insert(*provider);
+            } else {
+                report.invalid.push(InvalidHeader {
+                    number,
+                    provider: provider.to_string(),
+                });
+            }
+        }
+
+        let providers: Vec<String> = sources
+            .iter()
+            .map(|(provider, _)| *provider)
+            .filter(|provider| !block_headers.iter().any(|(served, _)| served == provider))
+            .map(str::to_string)
+            .collect();
+        if !providers.is_empty() {
+            report.missing.push(MissingHeader { number, providers });
+        }
+
+        let mut provider_fields: Vec<(&str, _)> = block_headers
+            .iter()
+            .map(|(provider, block_header)| (*provider, fields(block_header)))
+            .collect();
+        for index in 0..provider_fields[0].1.len() {
+            let (field, kind, _) = provider_fields[0].1[index];
+            let values: BTreeMap<String, Option<String>> = provider_fields
+                .iter_mut()
+                .map(|(provider, fields)| {
+                    let value = fields[index].2.take();
+                    (
+                        provider.to_string(),
+                        value.map(|value| kind.normalize(value)),
+                    )
+                })
+                .collect();
+            if values.values().collect::<BTreeSet<_>>().len() < 2 {
+                continue;
+            }
+
+            let expected = expected_value(&values, &valid);
+            let dissenters = match &expected {
+                Some(expected) => values
+                    .iter()
+                    .filter(|(_, value)| *value != expected)
+                    .map(|(provider, _)| provider.clone())
+                    .collect(),
+                None => vec![],
+            };
+            report.discrepancies.push(Discrepancy {
+                number,
+                field,
+                values,
+                expected,
+                dissenters,
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_prague};
+
+    fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(0, 5)
+    }
+
+    #[test]
+    fn test_consistent_providers() {
+        let mut chain = chain();
+        let mut reformatted = chain.clone();
+        for (block_header, other) in chain.iter_mut().zip(&mut reformatted) {
+            block_header.totaldifficulty = Some("0x400000000".to_string());
+            other.totaldifficulty = Some("17179869184".to_string());
+            other.block_hash = other.block_hash.to_uppercase().replace("0X", "0x");
+        }
+
+        let report = audit_providers(&[("a", &chain), ("b", &reformatted)]);
+        assert_eq!(report.block_count, 5);
+        assert!(report.is_consistent());
+    }
+
+    #[test]
+    fn test_provider_discrepancies() {
+        let chain = chain();
+        let mut stale = chain.clone();
+        stale[2].state_root = Some(format!("0x{}", "11".repeat(32)));
+        stale.pop();
+
+        let report = audit_providers(&[("a", &chain), ("b", &stale), ("c", &chain)]);
+        assert_eq!(
+            report.invalid,
+            vec![InvalidHeader {
+                number: 2,
+                provider: "b".to_string()
+            }]
+        );
+        assert_eq!(
+            report.missing,
+            vec![MissingHeader {
+                number: 4,
+                providers: vec!["b".to_string()]
+            }]
+        );
+
+        assert_eq!(report.discrepancies.len(), 1);
+        let discrepancy = &report.discrepancies[0];
+        assert_eq!((discrepancy.number, discrepancy.field), (2, "state_root"));
+        assert_eq!(discrepancy.expected, Some(chain[2].state_root.clone()));
+        assert_eq!(discrepancy.dissenters, vec!["b".to_string()]);
+        assert_eq!(report.dissent_counts().get("b"), Some(&1));
+
+        // With two providers, the header that verifies decides which one is wrong.
+        let report = audit_providers(&[("a", &chain), ("b", &stale)]);
+        assert_eq!(report.discrepancies[0].dissenters, vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_requests_hash_discrepancy() {
+        let chain = create_test_chain_prague(2);
+        let mut wrong = chain.clone();
+        wrong[1].requests_hash = Some(format!("0x{}", "11".repeat(32)));
+
+        let report = audit_providers(&[("a", &chain), ("b", &wrong), ("c", &chain)]);
+        assert_eq!(report.discrepancies.len(), 1);
+        let discrepancy = &report.discrepancies[0];
+        assert_eq!(discrepancy.field, "requests_hash");
+        assert_eq!(discrepancy.expected, Some(chain[1].requests_hash.clone()));
+        assert_eq!(discrepancy.dissenters, vec!["b".to_string()]);
+    }
+}
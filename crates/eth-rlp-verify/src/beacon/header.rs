@@ -0,0 +1,202 @@
+use super::BeaconProofError;
+use crate::eras::{BlockHeaderDencun, BlockHeaderPrague};
+use crate::ssz::{merkleize, uint64_chunk};
+use eth_rlp_types::BlockHeaderTrait;
+use primitive_types::H256;
+
+/// The length of an SSZ encoded `BeaconBlockHeader`: two `uint64` and three `Bytes32` fields.
+const BEACON_BLOCK_HEADER_SSZ_LENGTH: usize = 112;
+
+/// An execution block header committing to its parent beacon block root, as introduced by
+/// EIP-4788 in Dencun.
+pub trait PostDencunBlockHeader: BlockHeaderTrait {
+    fn parent_beacon_block_root(&self) -> H256;
+}
+
+impl PostDencunBlockHeader for BlockHeaderDencun {
+    fn parent_beacon_block_root(&self) -> H256 {
+        self.parent_beacon_block_root
+    }
+}
+
+impl PostDencunBlockHeader for BlockHeaderPrague {
+    fn parent_beacon_block_root(&self) -> H256 {
+        self.parent_beacon_block_root
+    }
+}
+
+/// A beacon chain block header, whose `hash_tree_root` is the beacon block root.
+///
+/// # Fields
+///
+/// - `slot`: The slot of the beacon block.
+/// - `proposer_index`: The index of the validator who proposed the block.
+/// - `parent_root`: The root of the parent beacon block.
+/// - `state_root`: The root of the beacon state after the block is processed.
+/// - `body_root`: The `hash_tree_root` of the beacon block body.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BeaconBlockHeader {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: H256,
+    pub state_root: H256,
+    pub body_root: H256,
+}
+
+impl BeaconBlockHeader {
+    /// Decodes an SSZ encoded beacon block header.
+    ///
+    /// # Arguments
+    ///
+    /// - `data`: The 112 bytes of the SSZ encoding.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, BeaconProofError>` containing the decoded header.
+    pub fn from_ssz_bytes(data: &[u8]) -> Result<Self, BeaconProofError> {
+        if data.len() != BEACON_BLOCK_HEADER_SSZ_LENGTH {
+            return Err(BeaconProofError::InvalidSszLength {
+                expected: BEACON_BLOCK_HEADER_SSZ_LENGTH,
+                got: data.len(),
+            });
+        }
+
+        let uint64_at = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        Ok(BeaconBlockHeader {
+            slot: uint64_at(0),
+            proposer_index: uint64_at(8),
+            parent_root: H256::from_slice(&data[16..48]),
+            state_root: H256::from_slice(&data[48..80]),
+            body_root: H256::from_slice(&data[80..112]),
+        })
+    }
+
+    /// Encodes the header using SSZ.
+    pub fn to_ssz_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(BEACON_BLOCK_HEADER_SSZ_LENGTH);
+        data.extend_from_slice(&self.slot.to_le_bytes());
+        data.extend_from_slice(&self.proposer_index.to_le_bytes());
+        data.extend_from_slice(self.parent_root.as_bytes());
+        data.extend_from_slice(self.state_root.as_bytes());
+        data.extend_from_slice(self.body_root.as_bytes());
+        data
+    }
+
+    /// Computes the SSZ `hash_tree_root` of the header, i.e. the beacon block root.
+    pub fn tree_hash_root(&self) -> H256 {
+        merkleize(
+            &[
+                uint64_chunk(self.slot),
+                uint64_chunk(self.proposer_index),
+                self.parent_root,
+                self.state_root,
+                self.body_root,
+            ],
+            3,
+        )
+    }
+}
+
+/// Verifies that an execution header commits to the given parent beacon block header.
+///
+/// This links a verified execution header to beacon chain data: the beacon block header of the
+/// parent slot must hash to the `parent_beacon_block_root` stored in the execution header.
+///
+/// # Arguments
+///
+/// - `execution_header`: A Dencun or later execution block header.
+/// - `beacon_header_ssz`: The SSZ encoded beacon block header of the parent beacon block.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the roots match.
+pub fn verify_parent_beacon_block_root<T: PostDencunBlockHeader>(
+    execution_header: &T,
+    beacon_header_ssz: &[u8],
+) -> Result<bool, BeaconProofError> {
+    let beacon_header = BeaconBlockHeader::from_ssz_bytes(beacon_header_ssz)?;
+    Ok(beacon_header.tree_hash_root() == execution_header.parent_beacon_block_root())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssz::{hash_pair, zero_hash};
+    use primitive_types::{H160, U256};
+
+    fn mock_beacon_header() -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: 8_626_200,
+            proposer_index: 1_234,
+            parent_root: H256::repeat_byte(1),
+            state_root: H256::repeat_byte(2),
+            body_root: H256::repeat_byte(3),
+        }
+    }
+
+    #[test]
+    fn test_beacon_block_header_ssz() {
+        let header = mock_beacon_header();
+        let encoded = header.to_ssz_bytes();
+        assert_eq!(BeaconBlockHeader::from_ssz_bytes(&encoded), Ok(header));
+        assert_eq!(
+            BeaconBlockHeader::from_ssz_bytes(&encoded[1..]),
+            Err(BeaconProofError::InvalidSszLength {
+                expected: 112,
+                got: 111
+            })
+        );
+
+        let expected = hash_pair(
+            &hash_pair(
+                &hash_pair(&uint64_chunk(8_626_200), &uint64_chunk(1_234)),
+                &hash_pair(&header.parent_root, &header.state_root),
+            ),
+            &hash_pair(&hash_pair(&header.body_root, &zero_hash(0)), &zero_hash(1)),
+        );
+        assert_eq!(header.tree_hash_root(), expected);
+    }
+
+    #[test]
+    fn test_verify_parent_beacon_block_root() {
+        let beacon_header = mock_beacon_header();
+        let mut execution_header = BlockHeaderDencun {
+            parent_hash: H256::zero(),
+            ommers_hash: H256::zero(),
+            beneficiary: H160::zero(),
+            state_root: H256::zero(),
+            transactions_root: H256::zero(),
+            receipts_root: H256::zero(),
+            logs_bloom: [0; 256],
+            difficulty: U256::zero(),
+            number: U256::zero(),
+            gas_limit: U256::zero(),
+            gas_used: U256::zero(),
+            timestamp: U256::zero(),
+            extra_data: vec![],
+            mix_hash: H256::zero(),
+            nonce: [0; 8],
+            base_fee_per_gas: U256::zero(),
+            withdrawals_root: H256::zero(),
+            parent_beacon_block_root: beacon_header.tree_hash_root(),
+            blob_gas_used: U256::zero(),
+            excess_blob_gas: U256::zero(),
+        };
+
+        let ssz = beacon_header.to_ssz_bytes();
+        assert_eq!(
+            verify_parent_beacon_block_root(&execution_header, &ssz),
+            Ok(true)
+        );
+
+        execution_header.parent_beacon_block_root = beacon_header.parent_root;
+        assert_eq!(
+            verify_parent_beacon_block_root(&execution_header, &ssz),
+            Ok(false)
+        );
+    }
+}
@@ -0,0 +1,487 @@
+use super::{BeaconFork, BeaconProofError};
+use crate::constants::{CAPELLA_START_SLOT, SLOTS_PER_HISTORICAL_ROOT};
+use crate::ssz::{hash_pair, is_valid_merkle_branch, merkleize, mix_in_length};
+use primitive_types::H256;
+
+/// The depth of the `block_roots` vector, `log2(SLOTS_PER_HISTORICAL_ROOT)`.
+const BLOCK_ROOTS_DEPTH: usize = 13;
+
+/// The depth of the `historical_roots` and `historical_summaries` lists,
+/// `log2(HISTORICAL_ROOTS_LIMIT)` with `HISTORICAL_ROOTS_LIMIT = 2^24`.
+const HISTORICAL_LIST_DEPTH: usize = 24;
+
+/// The position of the `historical_roots` field in the beacon state.
+const HISTORICAL_ROOTS_FIELD: u64 = 7;
+
+/// The position of the `historical_summaries` field in the beacon state.
+const HISTORICAL_SUMMARIES_FIELD: u64 = 27;
+
+/// Returns the depth and index of `execution_payload.block_hash` below a beacon block root.
+///
+/// The block hash is field 12 of the execution payload, which is field 9 of the block body, which
+/// is field 4 of the beacon block. Deneb grew the execution payload past 16 fields, adding a level
+/// to its tree.
+fn execution_block_hash_position(fork: BeaconFork) -> (usize, u64) {
+    let payload_depth = match fork {
+        BeaconFork::Bellatrix | BeaconFork::Capella => 4,
+        BeaconFork::Deneb | BeaconFork::Electra => 5,
+    };
+    let body_depth = 4;
+    let block_depth = 3;
+
+    let index = (4 << (body_depth + payload_depth)) | (9 << payload_depth) | 12;
+    (block_depth + body_depth + payload_depth, index)
+}
+
+/// Returns the depth of the beacon state tree, which Electra grew past 32 fields.
+fn beacon_state_depth(fork: BeaconFork) -> usize {
+    match fork {
+        BeaconFork::Bellatrix | BeaconFork::Capella | BeaconFork::Deneb => 5,
+        BeaconFork::Electra => 6,
+    }
+}
+
+fn check_proof_length(proof: &[H256], expected: usize) -> Result<(), BeaconProofError> {
+    if proof.len() != expected {
+        return Err(BeaconProofError::InvalidProofLength {
+            expected,
+            got: proof.len(),
+        });
+    }
+    Ok(())
+}
+
+/// A summary of `SLOTS_PER_HISTORICAL_ROOT` slots, as stored in the beacon state from Capella on.
+///
+/// # Fields
+///
+/// - `block_summary_root`: The `hash_tree_root` of the period's `block_roots` vector.
+/// - `state_summary_root`: The `hash_tree_root` of the period's `state_roots` vector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistoricalSummary {
+    pub block_summary_root: H256,
+    pub state_summary_root: H256,
+}
+
+impl HistoricalSummary {
+    /// Computes the SSZ `hash_tree_root` of the summary.
+    pub fn tree_hash_root(&self) -> H256 {
+        hash_pair(&self.block_summary_root, &self.state_summary_root)
+    }
+}
+
+/// Computes the SSZ `hash_tree_root` of a `historical_summaries` list.
+pub fn historical_summaries_root(historical_summaries: &[HistoricalSummary]) -> H256 {
+    let roots: Vec<H256> = historical_summaries
+        .iter()
+        .map(HistoricalSummary::tree_hash_root)
+        .collect();
+    mix_in_length(
+        merkleize(&roots, HISTORICAL_LIST_DEPTH),
+        historical_summaries.len(),
+    )
+}
+
+/// Verifies that an execution block hash is committed to in a beacon block.
+///
+/// The proof is the SSZ Merkle branch from `execution_payload.block_hash` up to the beacon block
+/// root, whose shape depends on the fork of `slot`.
+///
+/// # Arguments
+///
+/// - `block_hash`: The execution block hash, e.g. of a header verified with `verify_hash_paris`,
+///   `verify_hash_shapella` or `verify_hash_dencun`.
+/// - `slot`: The slot of the beacon block.
+/// - `beacon_block_root`: The `hash_tree_root` of the beacon block.
+/// - `proof`: The Merkle branch, bottom-up.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the proof is valid.
+pub fn verify_execution_block_proof(
+    block_hash: H256,
+    slot: u64,
+    beacon_block_root: H256,
+    proof: &[H256],
+) -> Result<bool, BeaconProofError> {
+    let fork = BeaconFork::at_slot(slot).ok_or(BeaconProofError::PreBellatrixSlot(slot))?;
+    let (depth, index) = execution_block_hash_position(fork);
+    check_proof_length(proof, depth)?;
+
+    Ok(is_valid_merkle_branch(
+        block_hash,
+        proof,
+        depth,
+        index,
+        beacon_block_root,
+    ))
+}
+
+/// Verifies that a beacon block root is part of a historical summary's `block_roots`.
+///
+/// # Arguments
+///
+/// - `beacon_block_root`: The `hash_tree_root` of the beacon block.
+/// - `slot`: The slot of the beacon block.
+/// - `proof`: The 13 chunk Merkle branch within the `block_roots` vector, bottom-up.
+/// - `block_summary_root`: The trusted `block_summary_root` of the summary covering `slot`.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the proof is valid.
+pub fn verify_beacon_block_proof(
+    beacon_block_root: H256,
+    slot: u64,
+    proof: &[H256],
+    block_summary_root: H256,
+) -> Result<bool, BeaconProofError> {
+    check_proof_length(proof, BLOCK_ROOTS_DEPTH)?;
+
+    Ok(is_valid_merkle_branch(
+        beacon_block_root,
+        proof,
+        BLOCK_ROOTS_DEPTH,
+        slot % SLOTS_PER_HISTORICAL_ROOT,
+        block_summary_root,
+    ))
+}
+
+/// Verifies that a beacon block root is part of a pre-Capella historical batch.
+///
+/// A historical batch holds the `block_roots` and `state_roots` vectors of a period, so the proof
+/// is the branch within `block_roots` followed by the root of `state_roots`.
+///
+/// # Arguments
+///
+/// - `beacon_block_root`: The `hash_tree_root` of the beacon block.
+/// - `slot`: The slot of the beacon block.
+/// - `proof`: The 14 chunk Merkle branch, bottom-up.
+/// - `historical_root`: The trusted `historical_roots` entry covering `slot`.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the proof is valid.
+pub fn verify_historical_batch_proof(
+    beacon_block_root: H256,
+    slot: u64,
+    proof: &[H256],
+    historical_root: H256,
+) -> Result<bool, BeaconProofError> {
+    check_proof_length(proof, BLOCK_ROOTS_DEPTH + 1)?;
+
+    Ok(is_valid_merkle_branch(
+        beacon_block_root,
+        proof,
+        BLOCK_ROOTS_DEPTH + 1,
+        slot % SLOTS_PER_HISTORICAL_ROOT,
+        historical_root,
+    ))
+}
+
+/// Verifies a `historical_summaries` list against a trusted beacon state root.
+///
+/// # Arguments
+///
+/// - `state_root`: The trusted beacon state root, e.g. of a finalized checkpoint.
+/// - `state_slot`: The slot of the beacon state, which determines its layout.
+/// - `historical_summaries`: The full `historical_summaries` list of the state.
+/// - `proof`: The Merkle branch of the `historical_summaries` field in the state, bottom-up.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the list matches the state.
+pub fn verify_historical_summaries(
+    state_root: H256,
+    state_slot: u64,
+    historical_summaries: &[HistoricalSummary],
+    proof: &[H256],
+) -> Result<bool, BeaconProofError> {
+    let fork = BeaconFork::at_slot(state_slot)
+        .filter(|fork| *fork >= BeaconFork::Capella)
+        .ok_or(BeaconProofError::UnsupportedSlot {
+            slot: state_slot,
+            proof: "historical_summaries",
+        })?;
+    let depth = beacon_state_depth(fork);
+    check_proof_length(proof, depth)?;
+
+    Ok(is_valid_merkle_branch(
+        historical_summaries_root(historical_summaries),
+        proof,
+        depth,
+        HISTORICAL_SUMMARIES_FIELD,
+        state_root,
+    ))
+}
+
+/// Verifies a `historical_roots` list against a trusted beacon state root.
+///
+/// The list stopped growing at Capella, so any post-Bellatrix state can be used.
+///
+/// # Arguments
+///
+/// - `state_root`: The trusted beacon state root.
+/// - `state_slot`: The slot of the beacon state, which determines its layout.
+/// - `historical_roots`: The full `historical_roots` list of the state.
+/// - `proof`: The Merkle branch of the `historical_roots` field in the state, bottom-up.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the list matches the state.
+pub fn verify_historical_roots(
+    state_root: H256,
+    state_slot: u64,
+    historical_roots: &[H256],
+    proof: &[H256],
+) -> Result<bool, BeaconProofError> {
+    let fork =
+        BeaconFork::at_slot(state_slot).ok_or(BeaconProofError::PreBellatrixSlot(state_slot))?;
+    let depth = beacon_state_depth(fork);
+    check_proof_length(proof, depth)?;
+
+    let list_root = mix_in_length(
+        merkleize(historical_roots, HISTORICAL_LIST_DEPTH),
+        historical_roots.len(),
+    );
+    Ok(is_valid_merkle_branch(
+        list_root,
+        proof,
+        depth,
+        HISTORICAL_ROOTS_FIELD,
+        state_root,
+    ))
+}
+
+/// A proof that a post-Capella execution block is canonical, through `historical_summaries`.
+///
+/// # Fields
+///
+/// - `slot`: The slot of the beacon block carrying the execution payload.
+/// - `beacon_block_root`: The `hash_tree_root` of that beacon block.
+/// - `beacon_block_proof`: The branch of the beacon block root in the summary's `block_roots`.
+/// - `execution_block_proof`: The branch of the execution block hash in the beacon block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockProofHistoricalSummaries {
+    pub slot: u64,
+    pub beacon_block_root: H256,
+    pub beacon_block_proof: Vec<H256>,
+    pub execution_block_proof: Vec<H256>,
+}
+
+/// A proof that a pre-Capella, post-merge execution block is canonical, through
+/// `historical_roots`.
+///
+/// # Fields
+///
+/// - `slot`: The slot of the beacon block carrying the execution payload.
+/// - `beacon_block_root`: The `hash_tree_root` of that beacon block.
+/// - `beacon_block_proof`: The branch of the beacon block root in the historical batch.
+/// - `execution_block_proof`: The branch of the execution block hash in the beacon block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockProofHistoricalRoots {
+    pub slot: u64,
+    pub beacon_block_root: H256,
+    pub beacon_block_proof: Vec<H256>,
+    pub execution_block_proof: Vec<H256>,
+}
+
+/// Verifies that an execution block hash is canonical using trusted `historical_summaries`.
+///
+/// The block hash is proven to be committed to in a beacon block, whose root is proven to be
+/// part of the historical summary covering its slot.
+///
+/// # Arguments
+///
+/// - `block_hash`: The execution block hash.
+/// - `proof`: The block proof.
+/// - `historical_summaries`: The trusted `historical_summaries`, e.g. checked with
+///   `verify_historical_summaries`.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the block is canonical.
+pub fn verify_block_proof_historical_summaries(
+    block_hash: H256,
+    proof: &BlockProofHistoricalSummaries,
+    historical_summaries: &[HistoricalSummary],
+) -> Result<bool, BeaconProofError> {
+    if proof.slot < CAPELLA_START_SLOT {
+        return Err(BeaconProofError::UnsupportedSlot {
+            slot: proof.slot,
+            proof: "historical_summaries",
+        });
+    }
+    let index = ((proof.slot - CAPELLA_START_SLOT) / SLOTS_PER_HISTORICAL_ROOT) as usize;
+    let summary = historical_summaries
+        .get(index)
+        .ok_or(BeaconProofError::HistoricalEntryNotFound(index))?;
+
+    Ok(verify_execution_block_proof(
+        block_hash,
+        proof.slot,
+        proof.beacon_block_root,
+        &proof.execution_block_proof,
+    )? && verify_beacon_block_proof(
+        proof.beacon_block_root,
+        proof.slot,
+        &proof.beacon_block_proof,
+        summary.block_summary_root,
+    )?)
+}
+
+/// Verifies that an execution block hash is canonical using trusted `historical_roots`.
+///
+/// The block hash is proven to be committed to in a beacon block, whose root is proven to be
+/// part of the historical batch covering its slot.
+///
+/// # Arguments
+///
+/// - `block_hash`: The execution block hash.
+/// - `proof`: The block proof.
+/// - `historical_roots`: The trusted `historical_roots`, e.g. checked with
+///   `verify_historical_roots`.
+///
+/// # Returns
+///
+/// A `Result<bool, BeaconProofError>` indicating whether the block is canonical.
+pub fn verify_block_proof_historical_roots(
+    block_hash: H256,
+    proof: &BlockProofHistoricalRoots,
+    historical_roots: &[H256],
+) -> Result<bool, BeaconProofError> {
+    if proof.slot >= CAPELLA_START_SLOT {
+        return Err(BeaconProofError::UnsupportedSlot {
+            slot: proof.slot,
+            proof: "historical_roots",
+        });
+    }
+    let index = (proof.slot / SLOTS_PER_HISTORICAL_ROOT) as usize;
+    let historical_root = historical_roots
+        .get(index)
+        .ok_or(BeaconProofError::HistoricalEntryNotFound(index))?;
+
+    Ok(verify_execution_block_proof(
+        block_hash,
+        proof.slot,
+        proof.beacon_block_root,
+        &proof.execution_block_proof,
+    )? && verify_historical_batch_proof(
+        proof.beacon_block_root,
+        proof.slot,
+        &proof.beacon_block_proof,
+        *historical_root,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{BELLATRIX_START_SLOT, DENEB_START_SLOT};
+    use crate::ssz::merkle_branch;
+
+    fn chunks(count: u64, seed: u64) -> Vec<H256> {
+        (0..count)
+            .map(|i| H256::from_low_u64_be(seed * 1000 + i))
+            .collect()
+    }
+
+    /// Builds a beacon block committing to `block_hash` and returns its root and the proof.
+    fn mock_beacon_block(block_hash: H256, payload_fields: u64) -> (H256, Vec<H256>) {
+        let payload_depth = if payload_fields > 16 { 5 } else { 4 };
+        let mut payload = chunks(payload_fields, 1);
+        payload[12] = block_hash;
+        let mut body = chunks(12, 2);
+        body[9] = merkleize(&payload, payload_depth);
+        let mut block = chunks(5, 3);
+        block[4] = merkleize(&body, 4);
+
+        let mut proof = merkle_branch(&payload, payload_depth, 12);
+        proof.extend(merkle_branch(&body, 4, 9));
+        proof.extend(merkle_branch(&block, 3, 4));
+        (merkleize(&block, 3), proof)
+    }
+
+    #[test]
+    fn test_verify_block_proof_historical_summaries() {
+        let block_hash = H256::repeat_byte(0xaa);
+        let slot = DENEB_START_SLOT + 100;
+        let (beacon_block_root, execution_block_proof) = mock_beacon_block(block_hash, 17);
+
+        let mut block_roots = chunks(8192, 4);
+        block_roots[(slot % SLOTS_PER_HISTORICAL_ROOT) as usize] = beacon_block_root;
+        let index = ((slot - CAPELLA_START_SLOT) / SLOTS_PER_HISTORICAL_ROOT) as usize;
+        let mut historical_summaries = vec![
+            HistoricalSummary {
+                block_summary_root: H256::zero(),
+                state_summary_root: H256::zero(),
+            };
+            index + 1
+        ];
+        historical_summaries[index].block_summary_root = merkleize(&block_roots, 13);
+
+        let proof = BlockProofHistoricalSummaries {
+            slot,
+            beacon_block_root,
+            beacon_block_proof: merkle_branch(&block_roots, 13, (slot % 8192) as usize),
+            execution_block_proof,
+        };
+        assert_eq!(
+            verify_block_proof_historical_summaries(block_hash, &proof, &historical_summaries),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_block_proof_historical_summaries(
+                H256::repeat_byte(0xbb),
+                &proof,
+                &historical_summaries
+            ),
+            Ok(false)
+        );
+
+        let mut state = chunks(28, 5);
+        state[27] = historical_summaries_root(&historical_summaries);
+        let state_root = merkleize(&state, 5);
+        let state_proof = merkle_branch(&state, 5, 27);
+        assert_eq!(
+            verify_historical_summaries(state_root, slot, &historical_summaries, &state_proof),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_historical_summaries(state_root, slot, &historical_summaries[1..], &state_proof),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn test_verify_block_proof_historical_roots() {
+        let block_hash = H256::repeat_byte(0xaa);
+        let slot = BELLATRIX_START_SLOT + 5000;
+        let (beacon_block_root, execution_block_proof) = mock_beacon_block(block_hash, 14);
+
+        let mut block_roots = chunks(8192, 4);
+        block_roots[(slot % SLOTS_PER_HISTORICAL_ROOT) as usize] = beacon_block_root;
+        let state_roots_root = H256::repeat_byte(0x55);
+        let mut historical_roots = chunks((slot / SLOTS_PER_HISTORICAL_ROOT) + 1, 6);
+        historical_roots[(slot / SLOTS_PER_HISTORICAL_ROOT) as usize] =
+            hash_pair(&merkleize(&block_roots, 13), &state_roots_root);
+
+        let mut beacon_block_proof = merkle_branch(&block_roots, 13, (slot % 8192) as usize);
+        beacon_block_proof.push(state_roots_root);
+        let proof = BlockProofHistoricalRoots {
+            slot,
+            beacon_block_root,
+            beacon_block_proof,
+            execution_block_proof,
+        };
+        assert_eq!(
+            verify_block_proof_historical_roots(block_hash, &proof, &historical_roots),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_block_proof_historical_roots(block_hash, &proof, &historical_roots[..10]),
+            Err(BeaconProofError::HistoricalEntryNotFound(
+                (slot / SLOTS_PER_HISTORICAL_ROOT) as usize
+            ))
+        );
+    }
+}
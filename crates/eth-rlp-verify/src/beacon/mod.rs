@@ -0,0 +1,62 @@
+mod header;
+mod historical;
+
+use crate::constants::{
+    BELLATRIX_START_SLOT, CAPELLA_START_SLOT, DENEB_START_SLOT, ELECTRA_START_SLOT,
+};
+use thiserror::Error;
+
+pub use header::{verify_parent_beacon_block_root, BeaconBlockHeader, PostDencunBlockHeader};
+pub use historical::{
+    historical_summaries_root, verify_beacon_block_proof, verify_block_proof_historical_roots,
+    verify_block_proof_historical_summaries, verify_execution_block_proof,
+    verify_historical_batch_proof, verify_historical_roots, verify_historical_summaries,
+    BlockProofHistoricalRoots, BlockProofHistoricalSummaries, HistoricalSummary,
+};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum BeaconProofError {
+    #[error("Slot {0} precedes the Bellatrix fork")]
+    PreBellatrixSlot(u64),
+    #[error("Slot {slot} cannot be proven with {proof}")]
+    UnsupportedSlot { slot: u64, proof: &'static str },
+    #[error("Invalid proof length: expected {expected}, got {got}")]
+    InvalidProofLength { expected: usize, got: usize },
+    #[error("No historical entry at index {0}")]
+    HistoricalEntryNotFound(usize),
+    #[error("Invalid SSZ length: expected {expected}, got {got}")]
+    InvalidSszLength { expected: usize, got: usize },
+}
+
+/// The beacon chain forks whose blocks carry an execution payload.
+///
+/// The layout of beacon blocks and beacon states changes between forks, which moves the fields
+/// that proofs point to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BeaconFork {
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+impl BeaconFork {
+    /// Determines the mainnet fork of a beacon chain slot.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the slot precedes Bellatrix, as such blocks carry no execution payload.
+    pub fn at_slot(slot: u64) -> Option<Self> {
+        if slot >= ELECTRA_START_SLOT {
+            Some(BeaconFork::Electra)
+        } else if slot >= DENEB_START_SLOT {
+            Some(BeaconFork::Deneb)
+        } else if slot >= CAPELLA_START_SLOT {
+            Some(BeaconFork::Capella)
+        } else if slot >= BELLATRIX_START_SLOT {
+            Some(BeaconFork::Bellatrix)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use super::parse_hash;
+use crate::verify_block;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+use std::ops::RangeInclusive;
+
+/// A block number found in several rows with different hashes.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `hashes`: The distinct hashes found for the block, in the order they were seen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate {
+    pub number: u64,
+    pub hashes: Vec<H256>,
+}
+
+/// A row whose block number is lower than the one of a row before it.
+///
+/// # Fields
+///
+/// - `index`: The position of the row in the source.
+/// - `number`: The block number of the row.
+/// - `previous_number`: The highest block number of the rows before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutOfOrderRow {
+    pub index: u64,
+    pub number: u64,
+    pub previous_number: u64,
+}
+
+/// The outcome of analyzing a collection of headers.
+///
+/// # Fields
+///
+/// - `row_count`: The number of rows analyzed.
+/// - `first`: The lowest block number found, if any.
+/// - `last`: The highest block number found, if any.
+/// - `missing`: The ranges of block numbers without any row.
+/// - `duplicates`: The block numbers found with different hashes.
+/// - `redundant_rows`: The number of rows repeating an already seen block number and hash.
+/// - `out_of_order`: The rows breaking the ascending block number order.
+/// - `invalid`: The block numbers of the rows failing hash verification.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RangeReport {
+    pub row_count: u64,
+    pub first: Option<u64>,
+    pub last: Option<u64>,
+    pub missing: Vec<RangeInclusive<u64>>,
+    pub duplicates: Vec<Duplicate>,
+    pub redundant_rows: u64,
+    pub out_of_order: Vec<OutOfOrderRow>,
+    pub invalid: Vec<u64>,
+}
+
+impl RangeReport {
+    /// Returns whether the headers form a complete, ordered and valid range.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.duplicates.is_empty()
+            && self.redundant_rows == 0
+            && self.out_of_order.is_empty()
+            && self.invalid.is_empty()
+    }
+}
+
+/// Analyzes headers one at a time for gaps, duplicates and ordering issues.
+///
+/// The analyzer expects rows in ascending block number order, e.g. from an `ORDER BY number`
+/// database cursor, and only keeps the hashes of the current block number: gaps and duplicates
+/// are found as rows go by, so memory does not grow with the number of rows. A row whose number
+/// is lower than one already seen is reported in `out_of_order` and is not checked for gaps or
+/// duplicates, as the rows it could fill or repeat are gone.
+///
+/// # Fields
+///
+/// - `expected_range`: The range the headers are expected to cover, if known.
+/// - `current`: The highest block number seen so far and its distinct hashes.
+/// - `next_expected`: The lowest block number not yet covered, once known.
+/// - `report`: The report being built.
+#[derive(Clone, Debug, Default)]
+pub struct RangeAnalyzer {
+    expected_range: Option<RangeInclusive<u64>>,
+    current: Option<(u64, Vec<H256>)>,
+    next_expected: Option<u64>,
+    report: RangeReport,
+}
+
+impl RangeAnalyzer {
+    /// Creates an analyzer reporting gaps between the lowest and highest block numbers found.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an analyzer reporting gaps within `expected_range`, including at its edges.
+    pub fn with_expected_range(expected_range: RangeInclusive<u64>) -> Self {
+        RangeAnalyzer {
+            next_expected: Some(*expected_range.start()),
+            expected_range: Some(expected_range),
+            ..Self::default()
+        }
+    }
+
+    /// Reports the block numbers from `next_expected` up to `end` as missing, within the expected
+    /// range.
+    fn report_gap(&mut self, end: u64) {
+        let Some(start) = self.next_expected else {
+            return;
+        };
+        let (start, end) = match &self.expected_range {
+            Some(range) => (start.max(*range.start()), end.min(*range.end())),
+            None => (start, end),
+        };
+        if start <= end {
+            self.report.missing.push(start..=end);
+        }
+    }
+
+    /// Reports the current block number as a duplicate if it was seen with several hashes.
+    fn flush_current(&mut self) {
+        if let Some((number, hashes)) = self.current.take() {
+            if hashes.len() > 1 {
+                self.report.duplicates.push(Duplicate { number, hashes });
+            }
+        }
+    }
+
+    /// Analyzes the next row.
+    pub fn push(&mut self, block_header: &VerifiableBlockHeader) {
+        let number = block_header.number as u64;
+        let index = self.report.row_count;
+        self.report.row_count += 1;
+        self.report.first = Some(self.report.first.map_or(number, |first| first.min(number)));
+        self.report.last = Some(self.report.last.map_or(number, |last| last.max(number)));
+
+        if !verify_block(number, block_header.clone(), &block_header.block_hash).unwrap_or_default()
+        {
+            self.report.invalid.push(number);
+        }
+
+        let hash = parse_hash(&block_header.block_hash).unwrap_or_default();
+        match &mut self.current {
+            Some((current, _)) if number < *current => {
+                self.report.out_of_order.push(OutOfOrderRow {
+                    index,
+                    number,
+                    previous_number: *current,
+                });
+            }
+            Some((current, hashes)) if number == *current => {
+                if hashes.contains(&hash) {
+                    self.report.redundant_rows += 1;
+                } else {
+                    hashes.push(hash);
+                }
+            }
+            _ => {
+                self.flush_current();
+                if let Some(end) = number.checked_sub(1) {
+                    self.report_gap(end);
+                }
+                self.current = Some((number, vec![hash]));
+                self.next_expected = Some(number.saturating_add(1));
+            }
+        }
+    }
+
+    /// Completes the analysis.
+    ///
+    /// # Returns
+    ///
+    /// The `RangeReport` of every row pushed.
+    pub fn finish(mut self) -> RangeReport {
+        self.flush_current();
+        if let Some(range) = &self.expected_range {
+            let end = *range.end();
+            if self.next_expected.is_some_and(|next| next <= end) {
+                self.report_gap(end);
+            }
+        }
+        self.report
+    }
+}
+
+/// Analyzes a collection of headers for gaps, duplicates and ordering issues.
+///
+/// # Arguments
+///
+/// - `block_headers`: The headers to analyze, expected in ascending block number order.
+///
+/// # Returns
+///
+/// The `RangeReport` of the headers.
+pub fn analyze_headers<'a, I>(block_headers: I) -> RangeReport
+where
+    I: IntoIterator<Item = &'a VerifiableBlockHeader>,
+{
+    let mut analyzer = RangeAnalyzer::new();
+    for block_header in block_headers {
+        analyzer.push(block_header);
+    }
+    analyzer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_genesis};
+
+    fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(100, 10)
+    }
+
+    #[test]
+    fn test_clean_range() {
+        let report = analyze_headers(&chain());
+        assert!(report.is_clean());
+        assert_eq!(report.row_count, 10);
+        assert_eq!((report.first, report.last), (Some(100), Some(109)));
+    }
+
+    #[test]
+    fn test_range_issues() {
+        let chain = chain();
+        let forked = create_test_chain_genesis(&chain[5].block_hash, 106, 1, 1);
+
+        let mut rows = chain.clone();
+        rows.drain(2..4);
+        rows.insert(5, forked[0].clone());
+        rows.push(chain[9].clone());
+        rows.push(chain[7].clone());
+        rows[0].gas_used += 1;
+
+        let mut analyzer = RangeAnalyzer::with_expected_range(98..=111);
+        for row in &rows {
+            analyzer.push(row);
+        }
+        let report = analyzer.finish();
+
+        assert_eq!(report.missing, vec![98..=99, 102..=103, 110..=111]);
+        assert_eq!(
+            report.duplicates,
+            vec![Duplicate {
+                number: 106,
+                hashes: vec![
+                    parse_hash(&chain[6].block_hash).unwrap(),
+                    parse_hash(&forked[0].block_hash).unwrap()
+                ],
+            }]
+        );
+        assert_eq!(report.redundant_rows, 1);
+        assert_eq!(
+            report.out_of_order,
+            vec![OutOfOrderRow {
+                index: 10,
+                number: 107,
+                previous_number: 109
+            }]
+        );
+        assert_eq!(report.invalid, vec![100]);
+        assert_eq!((report.first, report.last), (Some(100), Some(109)));
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,144 @@
+use super::{parse_hash, verify_header_hash, ChainError};
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+
+/// Verifies headers walking backward from a trusted block hash.
+///
+/// Headers are pulled one at a time from an iterator in descending block number order. Each
+/// header's hash is verified with the era machinery and must equal the hash committed to by the
+/// previously verified header, starting with the trusted hash. Only that hash is kept between
+/// headers, so arbitrarily long ranges can be verified from a streaming source.
+///
+/// The iterator yields each verified header, and stops after the first error.
+///
+/// # Fields
+///
+/// - `headers`: The source of headers, in descending block number order.
+/// - `expected_hash`: The hash the next header must have.
+/// - `last_number`: The number of the last verified header, if any.
+/// - `failed`: Whether an error has been yielded.
+pub struct BackwardVerifier<I> {
+    headers: I,
+    expected_hash: H256,
+    last_number: Option<u64>,
+    failed: bool,
+}
+
+impl<I: Iterator<Item = VerifiableBlockHeader>> BackwardVerifier<I> {
+    /// Creates a verifier starting from a trusted block hash.
+    ///
+    /// # Arguments
+    ///
+    /// - `trusted_hash`: The trusted hash of the first header of the stream, e.g. a recent
+    ///   finalized block.
+    /// - `headers`: The headers to verify, in descending block number order.
+    pub fn new<T: IntoIterator<IntoIter = I>>(trusted_hash: H256, headers: T) -> Self {
+        BackwardVerifier {
+            headers: headers.into_iter(),
+            expected_hash: trusted_hash,
+            last_number: None,
+            failed: false,
+        }
+    }
+
+    /// Returns the hash the next header must have, i.e. the parent hash of the last verified
+    /// header. It can be used as the trusted hash to resume verification from another source.
+    pub fn expected_hash(&self) -> H256 {
+        self.expected_hash
+    }
+
+    /// Returns the number of the last verified header, if any.
+    pub fn last_number(&self) -> Option<u64> {
+        self.last_number
+    }
+
+    fn verify(&mut self, header: &VerifiableBlockHeader) -> Result<(), ChainError> {
+        let number = header.number as u64;
+        if let Some(last_number) = self.last_number {
+            if number.checked_add(1) != Some(last_number) {
+                return Err(ChainError::NonConsecutiveBlock { number });
+            }
+        }
+
+        if verify_header_hash(header)? != self.expected_hash {
+            return Err(ChainError::HashMismatch { number });
+        }
+
+        self.expected_hash = parse_hash(&header.parent_hash.clone().unwrap_or_default())?;
+        self.last_number = Some(number);
+        Ok(())
+    }
+}
+
+impl<I: Iterator<Item = VerifiableBlockHeader>> Iterator for BackwardVerifier<I> {
+    type Item = Result<VerifiableBlockHeader, ChainError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let header = self.headers.next()?;
+        match self.verify(&header) {
+            Ok(()) => Some(Ok(header)),
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_genesis};
+
+    fn descending_chain() -> (H256, Vec<VerifiableBlockHeader>) {
+        let mut chain = create_test_chain(0, 10);
+        chain.reverse();
+        (parse_hash(&chain[0].block_hash).unwrap(), chain)
+    }
+
+    #[test]
+    fn test_backward_verification() {
+        let (trusted_hash, chain) = descending_chain();
+        let mut verifier = BackwardVerifier::new(trusted_hash, chain.clone());
+        for expected in &chain {
+            assert_eq!(&verifier.next().unwrap().unwrap(), expected);
+        }
+        assert!(verifier.next().is_none());
+        assert_eq!(verifier.last_number(), Some(0));
+        assert_eq!(verifier.expected_hash(), H256::zero());
+    }
+
+    #[test]
+    fn test_backward_verification_failures() {
+        let (trusted_hash, chain) = descending_chain();
+
+        let mut verifier = BackwardVerifier::new(H256::repeat_byte(1), chain.clone());
+        assert!(matches!(
+            verifier.next(),
+            Some(Err(ChainError::HashMismatch { number: 9 }))
+        ));
+        assert!(verifier.next().is_none());
+
+        let mut gapped = chain.clone();
+        gapped.remove(3);
+        let results: Vec<_> = BackwardVerifier::new(trusted_hash, gapped).collect();
+        assert_eq!(results.len(), 4);
+        assert!(matches!(
+            results[3],
+            Err(ChainError::NonConsecutiveBlock { number: 5 })
+        ));
+
+        let forged = create_test_chain_genesis(&format!("{:?}", H256::zero()), 0, 5, 1);
+        let mut spliced = chain[..5].to_vec();
+        spliced.extend(forged.into_iter().rev());
+        let results: Vec<_> = BackwardVerifier::new(trusted_hash, spliced).collect();
+        assert!(matches!(
+            results[5],
+            Err(ChainError::HashMismatch { number: 4 })
+        ));
+    }
+}
@@ -0,0 +1,301 @@
+use super::{parse_hash, verify_header_hash, ChainError};
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::{H256, U256};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// The rule used to pick the canonical branch of a `ForkTree`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForkChoice {
+    /// The branch with the highest total difficulty wins, as before the merge.
+    TotalDifficulty,
+    /// The longest branch containing the given finalized block wins, as after the merge.
+    Finalized(H256),
+}
+
+/// A block of a `ForkTree`.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `parent_hash`: The hash of the parent block.
+/// - `total_difficulty`: The total difficulty of the chain up to this block. It is relative to
+///   the first ingested block of the branch if that block has no known total difficulty.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForkNode {
+    pub number: u64,
+    pub parent_hash: H256,
+    pub total_difficulty: U256,
+}
+
+/// A branch competing with the canonical one.
+///
+/// # Fields
+///
+/// - `tip`: The hash of the last block of the branch.
+/// - `tip_number`: The number of the last block of the branch.
+/// - `common_ancestor`: The hash of the last block shared with the canonical branch.
+/// - `ancestor_number`: The number of the common ancestor.
+/// - `reorg_depth`: The number of canonical blocks that would be reverted by switching to the
+///   branch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fork {
+    pub tip: H256,
+    pub tip_number: u64,
+    pub common_ancestor: H256,
+    pub ancestor_number: u64,
+    pub reorg_depth: u64,
+}
+
+/// A tree of verified headers keyed by block hash, tracking competing branches.
+///
+/// Headers from several providers can be ingested in any mix, as long as parents are ingested
+/// before their children. Every header is verified with the era machinery before being added.
+///
+/// # Fields
+///
+/// - `nodes`: The ingested blocks, by hash.
+/// - `children`: The hashes of the known children of each block.
+#[derive(Clone, Debug, Default)]
+pub struct ForkTree {
+    nodes: HashMap<H256, ForkNode>,
+    children: HashMap<H256, Vec<H256>>,
+}
+
+impl ForkTree {
+    /// Creates an empty fork tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifies a header and adds it to the tree. Headers already in the tree are ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<H256, ChainError>` containing the hash of the block.
+    pub fn ingest(&mut self, block_header: &VerifiableBlockHeader) -> Result<H256, ChainError> {
+        let hash = verify_header_hash(block_header)?;
+        if self.nodes.contains_key(&hash) {
+            return Ok(hash);
+        }
+
+        let parent_hash = parse_hash(&block_header.parent_hash.clone().unwrap_or_default())?;
+        let difficulty = U256::from_str(&block_header.difficulty.clone().unwrap_or_default())
+            .unwrap_or_default();
+        let total_difficulty = match self.nodes.get(&parent_hash) {
+            Some(parent) => parent.total_difficulty + difficulty,
+            None => block_header
+                .totaldifficulty
+                .as_deref()
+                .and_then(|total_difficulty| U256::from_str(total_difficulty).ok())
+                .unwrap_or(difficulty),
+        };
+
+        self.nodes.insert(
+            hash,
+            ForkNode {
+                number: block_header.number as u64,
+                parent_hash,
+                total_difficulty,
+            },
+        );
+        self.children.entry(parent_hash).or_default().push(hash);
+        Ok(hash)
+    }
+
+    /// Returns the block with the given hash, if it was ingested.
+    pub fn get(&self, hash: &H256) -> Option<&ForkNode> {
+        self.nodes.get(hash)
+    }
+
+    /// Returns the number of blocks in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the tree holds no blocks.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the hashes of the blocks without children, i.e. the heads of every branch.
+    pub fn tips(&self) -> Vec<H256> {
+        let mut tips: Vec<H256> = self
+            .nodes
+            .keys()
+            .filter(|hash| !self.children.contains_key(hash))
+            .copied()
+            .collect();
+        tips.sort_by_key(|hash| (self.nodes[hash].number, *hash));
+        tips
+    }
+
+    /// Returns the block numbers at which more than one block was ingested, in ascending order.
+    pub fn competing_heights(&self) -> Vec<u64> {
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for node in self.nodes.values() {
+            *counts.entry(node.number).or_default() += 1;
+        }
+        let mut heights: Vec<u64> = counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(number, _)| number)
+            .collect();
+        heights.sort_unstable();
+        heights
+    }
+
+    /// Iterates over a block and its ingested ancestors, from the block downwards.
+    fn ancestors(&self, hash: H256) -> impl Iterator<Item = H256> + '_ {
+        std::iter::successors(
+            self.nodes.contains_key(&hash).then_some(hash),
+            move |hash| {
+                let parent_hash = self.nodes[hash].parent_hash;
+                self.nodes.contains_key(&parent_hash).then_some(parent_hash)
+            },
+        )
+    }
+
+    /// Returns the last block shared by the branches of `a` and `b`, if they are connected.
+    pub fn common_ancestor(&self, a: H256, b: H256) -> Option<H256> {
+        let ancestors: HashSet<H256> = self.ancestors(a).collect();
+        self.ancestors(b).find(|hash| ancestors.contains(hash))
+    }
+
+    /// Returns the number of blocks of `old_head`'s branch reverted when switching to `new_head`.
+    pub fn reorg_depth(&self, old_head: H256, new_head: H256) -> Option<u64> {
+        let ancestor = self.common_ancestor(old_head, new_head)?;
+        Some(self.nodes[&old_head].number - self.nodes[&ancestor].number)
+    }
+
+    /// Selects the head of the canonical branch.
+    ///
+    /// # Arguments
+    ///
+    /// - `fork_choice`: The rule used to compare branches.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<H256, ChainError>` containing the hash of the canonical head, or an error if the
+    /// tree is empty, the finalized block is unknown, or several branches tie.
+    pub fn canonical_tip(&self, fork_choice: ForkChoice) -> Result<H256, ChainError> {
+        let tips = self.tips();
+        let candidates: Vec<(H256, U256)> = match fork_choice {
+            ForkChoice::TotalDifficulty => tips
+                .into_iter()
+                .map(|tip| (tip, self.nodes[&tip].total_difficulty))
+                .collect(),
+            ForkChoice::Finalized(finalized) => {
+                if !self.nodes.contains_key(&finalized) {
+                    return Err(ChainError::UnknownBlock { hash: finalized });
+                }
+                tips.into_iter()
+                    .filter(|tip| self.ancestors(*tip).any(|hash| hash == finalized))
+                    .map(|tip| (tip, U256::from(self.nodes[&tip].number)))
+                    .collect()
+            }
+        };
+
+        let best = candidates
+            .iter()
+            .map(|(_, weight)| *weight)
+            .max()
+            .ok_or(ChainError::EmptyTree)?;
+        let mut best_tips = candidates.iter().filter(|(_, weight)| *weight == best);
+        match (best_tips.next(), best_tips.next()) {
+            (Some((tip, _)), None) => Ok(*tip),
+            _ => Err(ChainError::AmbiguousForkChoice),
+        }
+    }
+
+    /// Reports every branch competing with the canonical one.
+    ///
+    /// # Arguments
+    ///
+    /// - `fork_choice`: The rule used to select the canonical branch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<Fork>, ChainError>` with one entry per non-canonical tip.
+    pub fn forks(&self, fork_choice: ForkChoice) -> Result<Vec<Fork>, ChainError> {
+        let canonical_tip = self.canonical_tip(fork_choice)?;
+        let canonical_number = self.nodes[&canonical_tip].number;
+
+        Ok(self
+            .tips()
+            .into_iter()
+            .filter(|tip| *tip != canonical_tip)
+            .filter_map(|tip| {
+                let common_ancestor = self.common_ancestor(canonical_tip, tip)?;
+                let ancestor_number = self.nodes[&common_ancestor].number;
+                Some(Fork {
+                    tip,
+                    tip_number: self.nodes[&tip].number,
+                    common_ancestor,
+                    ancestor_number,
+                    reorg_depth: canonical_number - ancestor_number,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_genesis};
+
+    /// Builds a tree with a main chain of blocks 0 to 9 and a fork of blocks 6 to 8 on block 5.
+    fn forked_tree() -> (ForkTree, Vec<H256>, Vec<H256>) {
+        let main = create_test_chain(0, 10);
+        let fork = create_test_chain_genesis(&main[5].block_hash, 6, 3, 1);
+
+        let mut tree = ForkTree::new();
+        let main: Vec<H256> = main.iter().map(|h| tree.ingest(h).unwrap()).collect();
+        let fork: Vec<H256> = fork.iter().map(|h| tree.ingest(h).unwrap()).collect();
+        (tree, main, fork)
+    }
+
+    #[test]
+    fn test_fork_detection() {
+        let (tree, main, fork) = forked_tree();
+        assert_eq!(tree.len(), 13);
+        assert_eq!(tree.tips(), vec![fork[2], main[9]]);
+        assert_eq!(tree.competing_heights(), vec![6, 7, 8]);
+        assert_eq!(tree.common_ancestor(main[9], fork[2]), Some(main[5]));
+        assert_eq!(tree.reorg_depth(main[9], fork[2]), Some(4));
+        assert_eq!(tree.reorg_depth(fork[2], main[9]), Some(3));
+    }
+
+    #[test]
+    fn test_fork_choice() {
+        let (tree, main, fork) = forked_tree();
+        assert_eq!(
+            tree.canonical_tip(ForkChoice::TotalDifficulty).unwrap(),
+            main[9]
+        );
+        assert_eq!(
+            tree.forks(ForkChoice::TotalDifficulty).unwrap(),
+            vec![Fork {
+                tip: fork[2],
+                tip_number: 8,
+                common_ancestor: main[5],
+                ancestor_number: 5,
+                reorg_depth: 4,
+            }]
+        );
+
+        assert_eq!(
+            tree.canonical_tip(ForkChoice::Finalized(fork[0])).unwrap(),
+            fork[2]
+        );
+        assert_eq!(
+            tree.canonical_tip(ForkChoice::Finalized(main[2])).unwrap(),
+            main[9]
+        );
+        assert!(matches!(
+            tree.canonical_tip(ForkChoice::Finalized(H256::repeat_byte(1))),
+            Err(ChainError::UnknownBlock { .. })
+        ));
+    }
+}
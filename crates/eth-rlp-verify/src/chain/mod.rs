@@ -0,0 +1,66 @@
+mod analysis;
+mod backward;
+mod forks;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod verifier;
+
+use crate::verify_block;
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError};
+use primitive_types::H256;
+use std::str::FromStr;
+use thiserror::Error;
+
+pub use analysis::{analyze_headers, Duplicate, OutOfOrderRow, RangeAnalyzer, RangeReport};
+pub use backward::BackwardVerifier;
+pub use forks::{Fork, ForkChoice, ForkNode, ForkTree};
+#[cfg(feature = "parallel")]
+pub use parallel::verify_blocks_parallel;
+pub use verifier::{BlockResult, ChainVerifier};
+
+#[derive(Error, Debug)]
+pub enum ChainError {
+    #[error("Block {number} has an invalid hash")]
+    InvalidBlock { number: u64 },
+    #[error("Hash of block {number} does not match the hash committed to by its child")]
+    HashMismatch { number: u64 },
+    #[error("Block {number} does not follow the previous block")]
+    NonConsecutiveBlock { number: u64 },
+    #[error("Parent hash of block {number} does not match the previous block")]
+    BrokenLink { number: u64 },
+    #[error("Block {hash:?} is unknown")]
+    UnknownBlock { hash: H256 },
+    #[error("No block has been ingested")]
+    EmptyTree,
+    #[error("Several branches are equally good candidates")]
+    AmbiguousForkChoice,
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+}
+
+pub(crate) fn parse_hash(hash: &str) -> Result<H256, ChainError> {
+    Ok(H256::from_str(hash).map_err(BlockHeaderError::from)?)
+}
+
+/// Verifies the hash of a header with the era machinery.
+///
+/// # Returns
+///
+/// A `Result<H256, ChainError>` containing the verified block hash.
+pub(crate) fn verify_header_hash(header: &VerifiableBlockHeader) -> Result<H256, ChainError> {
+    let number = header.number as u64;
+    if !verify_block(number, header.clone(), &header.block_hash).unwrap_or_default() {
+        return Err(ChainError::InvalidBlock { number });
+    }
+    parse_hash(&header.block_hash)
+}
+
+/// Returns whether a block follows the `previous` block, given as its number and claimed hash.
+pub(crate) fn follows(
+    previous: (u64, Option<H256>),
+    number: u64,
+    parent_hash: Option<H256>,
+) -> bool {
+    let (previous_number, previous_hash) = previous;
+    number == previous_number + 1 && parent_hash.is_some() && parent_hash == previous_hash
+}
@@ -0,0 +1,75 @@
+use super::{follows, parse_hash, BlockResult};
+use crate::verify_block;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use rayon::prelude::*;
+
+/// Verifies a batch of headers using every available core.
+///
+/// Block hashes, which dominate the cost of verification, are computed in parallel. Parent links
+/// are then checked sequentially, in order, so that the results are identical to pushing the
+/// headers through a `ChainVerifier`.
+///
+/// # Arguments
+///
+/// - `block_headers`: The headers to verify, in ascending block number order.
+///
+/// # Returns
+///
+/// A `Vec<BlockResult>` holding the result of each header, at the header's index.
+pub fn verify_blocks_parallel(block_headers: &[VerifiableBlockHeader]) -> Vec<BlockResult> {
+    let hash_valid: Vec<bool> = block_headers
+        .par_iter()
+        .map(|block_header| {
+            verify_block(
+                block_header.number as u64,
+                block_header.clone(),
+                &block_header.block_hash,
+            )
+            .unwrap_or_default()
+        })
+        .collect();
+
+    let mut previous = None;
+    block_headers
+        .iter()
+        .zip(hash_valid)
+        .map(|(block_header, hash_valid)| {
+            let number = block_header.number as u64;
+            let parent_hash = block_header
+                .parent_hash
+                .as_deref()
+                .and_then(|parent_hash| parse_hash(parent_hash).ok());
+            let linked = previous.is_none_or(|previous| follows(previous, number, parent_hash));
+            previous = Some((number, parse_hash(&block_header.block_hash).ok()));
+
+            BlockResult {
+                number,
+                block_hash: block_header.block_hash.clone(),
+                hash_valid,
+                linked,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainVerifier;
+    use crate::test_helpers::create_test_chain;
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let mut headers = create_test_chain(0, 200);
+        headers[50].gas_used += 1;
+        headers.remove(120);
+
+        let sequential: Vec<_> = ChainVerifier::new().verify_iter(headers.clone()).collect();
+        let parallel = verify_blocks_parallel(&headers);
+        assert_eq!(parallel, sequential);
+        assert_eq!(
+            parallel.iter().filter(|result| !result.is_valid()).count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use super::{follows, parse_hash};
+use crate::verify_block;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+use serde::Serialize;
+
+/// The verification result of a single block.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `block_hash`: The block hash claimed by the header.
+/// - `hash_valid`: Whether the claimed hash matches the hash computed from the header.
+/// - `linked`: Whether the block follows the previous block, i.e. has the next block number and
+///   its parent hash is the previous block's hash. Always `true` for the first block.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BlockResult {
+    pub number: u64,
+    pub block_hash: String,
+    pub hash_valid: bool,
+    pub linked: bool,
+}
+
+impl BlockResult {
+    /// Returns whether the block passed every check.
+    pub fn is_valid(&self) -> bool {
+        self.hash_valid && self.linked
+    }
+}
+
+/// A stateful verifier accepting headers one at a time, in ascending block number order.
+///
+/// Only the number and hash of the previous header are kept, so that a chain of any length can be
+/// verified in constant memory, e.g. from a database cursor. Headers are taken by value and never
+/// cloned.
+///
+/// # Fields
+///
+/// - `previous`: The number and claimed hash of the previous header.
+/// - `verified_count`: The number of headers pushed so far.
+/// - `failure_count`: The number of headers that failed a check.
+#[derive(Clone, Debug, Default)]
+pub struct ChainVerifier {
+    previous: Option<(u64, Option<H256>)>,
+    verified_count: u64,
+    failure_count: u64,
+}
+
+impl ChainVerifier {
+    /// Creates a verifier expecting any block as the first header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verifies the next header of the chain.
+    ///
+    /// # Arguments
+    ///
+    /// - `block_header`: The header following the previously pushed one.
+    ///
+    /// # Returns
+    ///
+    /// The `BlockResult` of the header.
+    pub fn push(&mut self, mut block_header: VerifiableBlockHeader) -> BlockResult {
+        let number = block_header.number as u64;
+        let block_hash = std::mem::take(&mut block_header.block_hash);
+        let hash = parse_hash(&block_hash).ok();
+        let parent_hash = block_header
+            .parent_hash
+            .as_deref()
+            .and_then(|parent_hash| parse_hash(parent_hash).ok());
+
+        let linked = self
+            .previous
+            .is_none_or(|previous| follows(previous, number, parent_hash));
+        let hash_valid = verify_block(number, block_header, &block_hash).unwrap_or_default();
+
+        self.previous = Some((number, hash));
+        self.verified_count += 1;
+        let result = BlockResult {
+            number,
+            block_hash,
+            hash_valid,
+            linked,
+        };
+        if !result.is_valid() {
+            self.failure_count += 1;
+        }
+        result
+    }
+
+    /// Verifies every header of an iterator, yielding per-block results lazily.
+    pub fn verify_iter<'a, I>(
+        &'a mut self,
+        block_headers: I,
+    ) -> impl Iterator<Item = BlockResult> + 'a
+    where
+        I: IntoIterator<Item = VerifiableBlockHeader>,
+        I::IntoIter: 'a,
+    {
+        block_headers
+            .into_iter()
+            .map(move |block_header| self.push(block_header))
+    }
+
+    /// Returns the number of headers pushed so far.
+    pub fn verified_count(&self) -> u64 {
+        self.verified_count
+    }
+
+    /// Returns the number of headers that failed a check.
+    pub fn failure_count(&self) -> u64 {
+        self.failure_count
+    }
+
+    /// Returns whether every header pushed so far is valid.
+    pub fn is_valid(&self) -> bool {
+        self.failure_count == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_chain;
+
+    fn chain(length: u64) -> Vec<VerifiableBlockHeader> {
+        create_test_chain(1000, length)
+    }
+
+    #[test]
+    fn test_chain_verifier() {
+        let results: Vec<_> = ChainVerifier::new().verify_iter(chain(20)).collect();
+        assert_eq!(results.len(), 20);
+        assert!(results.iter().all(BlockResult::is_valid));
+        assert_eq!(results[19].number, 1019);
+    }
+
+    #[test]
+    fn test_chain_verifier_failures() {
+        let mut headers = chain(6);
+        headers[2].gas_used += 1;
+        headers.remove(4);
+
+        let mut verifier = ChainVerifier::new();
+        let results: Vec<_> = verifier.verify_iter(headers).collect();
+        let validity: Vec<_> = results
+            .iter()
+            .map(|result| (result.hash_valid, result.linked))
+            .collect();
+        assert_eq!(
+            validity,
+            vec![
+                (true, true),
+                (true, true),
+                (false, true),
+                (true, true),
+                (true, false)
+            ]
+        );
+        assert_eq!(verifier.verified_count(), 5);
+        assert_eq!(verifier.failure_count(), 2);
+        assert!(!verifier.is_valid());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::verify_block;
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError};
+use primitive_types::H256;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The mainnet checkpoint table shipped with the crate, one `number,hash` line per checkpoint in
+/// ascending block order.
+///
+/// The shipped table only holds the genesis block. A denser table is generated from a trusted node
+/// with the `checkpoints` example, which checks every returned header against its hash:
+///
+/// ```text
+/// cargo run --example checkpoints --features rpc -- <node url> <last block> > checkpoints/mainnet.csv
+/// ```
+pub const MAINNET_CHECKPOINT_TABLE: &str = include_str!("../checkpoints/mainnet.csv");
+
+/// Well-known mainnet block hashes shipped with the crate, in addition to
+/// `MAINNET_CHECKPOINT_TABLE`.
+///
+/// The list covers the genesis block, the DAO fork and both sides of the merge.
+pub const MAINNET_CHECKPOINTS: [(u64, &str); 5] = [
+    (
+        0,
+        "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+    ),
+    (
+        1,
+        "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6",
+    ),
+    (
+        1_920_000,
+        "0x4985f5ca3d2afbec36529aa96f74de3cc10a2a4a6c44f2157a57d2c6059a11bb",
+    ),
+    (
+        15_537_393,
+        "0x55b11b918355b1ef9c5db810302ebad0bf2544255b530cdce90674d5887bb286",
+    ),
+    (
+        15_537_394,
+        "0x56a9bb0302da44b8c0b3df540781424684c3af04d0b7a38d72842b762076a664",
+    ),
+];
+
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    #[error("Checkpoint for block {number} conflicts with an existing one")]
+    ConflictingCheckpoint { number: u64 },
+    #[error("Segment is empty")]
+    EmptySegment,
+    #[error("Block {number} has an invalid hash")]
+    InvalidBlock { number: u64 },
+    #[error("Block {number} does not follow the previous block")]
+    NonConsecutiveBlock { number: u64 },
+    #[error("Parent hash of block {number} does not match the previous block")]
+    BrokenLink { number: u64 },
+    #[error("Block {number} does not match the trusted checkpoint")]
+    CheckpointMismatch { number: u64 },
+    #[error("Segment does not link to any trusted checkpoint")]
+    NotAnchored,
+    #[error("Invalid checkpoint on line {line}")]
+    InvalidCheckpoint { line: usize },
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+}
+
+fn parse_hash(hash: &str) -> Result<H256, CheckpointError> {
+    Ok(H256::from_str(hash).map_err(BlockHeaderError::from)?)
+}
+
+/// A trusted block hash at a given height.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `hash`: The trusted hash of the block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Checkpoint {
+    pub number: u64,
+    pub hash: H256,
+}
+
+/// Parses a checkpoint table, one `number,hash` line per checkpoint.
+///
+/// Blank lines and lines starting with `#` are skipped.
+///
+/// # Returns
+///
+/// A `Result<Vec<Checkpoint>, CheckpointError>` with the checkpoints in file order, failing on the
+/// first malformed line.
+pub fn parse_checkpoints(table: &str) -> Result<Vec<Checkpoint>, CheckpointError> {
+    table
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            let invalid = || CheckpointError::InvalidCheckpoint { line: index + 1 };
+            let (number, hash) = line.trim().split_once(',').ok_or_else(invalid)?;
+            Ok(Checkpoint {
+                number: number.parse().map_err(|_| invalid())?,
+                hash: H256::from_str(hash).map_err(|_| invalid())?,
+            })
+        })
+        .collect()
+}
+
+/// A set of trusted block hashes, indexed by block number.
+#[derive(Clone, Debug, Default)]
+pub struct CheckpointStore {
+    checkpoints: BTreeMap<u64, H256>,
+}
+
+impl CheckpointStore {
+    /// Creates an empty checkpoint store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a checkpoint store holding the built-in `MAINNET_CHECKPOINT_TABLE` and
+    /// `MAINNET_CHECKPOINTS`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in table is malformed, rather than trusting fewer checkpoints.
+    #[allow(clippy::expect_used)]
+    pub fn mainnet() -> Self {
+        let table = parse_checkpoints(MAINNET_CHECKPOINT_TABLE)
+            .expect("built-in checkpoint table is valid");
+        let checkpoints = table
+            .into_iter()
+            .map(|checkpoint| (checkpoint.number, checkpoint.hash))
+            .chain(
+                MAINNET_CHECKPOINTS
+                    .iter()
+                    .filter_map(|(number, hash)| Some((*number, H256::from_str(hash).ok()?))),
+            )
+            .collect();
+        CheckpointStore { checkpoints }
+    }
+
+    /// Adds a trusted checkpoint.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<(), CheckpointError>`, failing if a different hash is already trusted for the
+    /// same block number.
+    pub fn insert(&mut self, checkpoint: Checkpoint) -> Result<(), CheckpointError> {
+        match self.checkpoints.get(&checkpoint.number) {
+            Some(hash) if *hash != checkpoint.hash => Err(CheckpointError::ConflictingCheckpoint {
+                number: checkpoint.number,
+            }),
+            _ => {
+                self.checkpoints.insert(checkpoint.number, checkpoint.hash);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the trusted hash of a block, if any.
+    pub fn get(&self, number: u64) -> Option<H256> {
+        self.checkpoints.get(&number).copied()
+    }
+
+    /// Returns the number of checkpoints.
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    /// Returns whether the store holds no checkpoints.
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Returns the checkpoints whose block numbers fall within the given range, in order.
+    pub fn range(&self, start: u64, end: u64) -> impl Iterator<Item = Checkpoint> + '_ {
+        self.checkpoints
+            .range(start..=end)
+            .map(|(number, hash)| Checkpoint {
+                number: *number,
+                hash: *hash,
+            })
+    }
+
+    /// Verifies a chain segment and checks that it is anchored to a trusted checkpoint.
+    ///
+    /// Unlike `are_blocks_and_chain_valid`, which only proves that a slice is internally
+    /// consistent, this requires the segment to link to a trusted hash, so that a fully forged
+    /// chain is rejected. The segment is anchored if its first block's parent, or any of its
+    /// blocks, is a checkpoint. Since every block is linked to the previous one, an anchor at
+    /// either end vouches for the whole segment.
+    ///
+    /// # Arguments
+    ///
+    /// - `block_headers`: The headers of the segment, in ascending block number order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Checkpoint, CheckpointError>` containing the checkpoint the segment is anchored
+    /// to, or an error describing the first problem found.
+    pub fn verify_segment(
+        &self,
+        block_headers: &[VerifiableBlockHeader],
+    ) -> Result<Checkpoint, CheckpointError> {
+        let (first, last) = match (block_headers.first(), block_headers.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(CheckpointError::EmptySegment),
+        };
+
+        let mut hashes = BTreeMap::new();
+        for (i, block) in block_headers.iter().enumerate() {
+            let number = block.number as u64;
+            if !verify_block(number, block.clone(), &block.block_hash).unwrap_or_default() {
+                return Err(CheckpointError::InvalidBlock { number });
+            }
+
+            if i != 0 {
+                let previous_block = &block_headers[i - 1];
+                if number != previous_block.number as u64 + 1 {
+                    return Err(CheckpointError::NonConsecutiveBlock { number });
+                }
+                let parent_hash = parse_hash(&block.parent_hash.clone().unwrap_or_default())?;
+                if parent_hash != parse_hash(&previous_block.block_hash)? {
+                    return Err(CheckpointError::BrokenLink { number });
+                }
+            }
+            hashes.insert(number, parse_hash(&block.block_hash)?);
+        }
+
+        // The parent of the first block is covered too, as its hash is committed to.
+        let first_number = first.number as u64;
+        if let Some(parent_number) = first_number.checked_sub(1) {
+            hashes.insert(
+                parent_number,
+                parse_hash(&first.parent_hash.clone().unwrap_or_default())?,
+            );
+        }
+
+        let mut anchor = None;
+        let range_start = first_number.saturating_sub(1);
+        for checkpoint in self.range(range_start, last.number as u64) {
+            if hashes.get(&checkpoint.number) != Some(&checkpoint.hash) {
+                return Err(CheckpointError::CheckpointMismatch {
+                    number: checkpoint.number,
+                });
+            }
+            anchor.get_or_insert(checkpoint);
+        }
+        anchor.ok_or(CheckpointError::NotAnchored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_genesis};
+
+    fn zero_hash() -> String {
+        format!("{:?}", H256::zero())
+    }
+
+    #[test]
+    fn test_mainnet_checkpoint_table() {
+        // Every line other than the header is a checkpoint, in ascending order from genesis.
+        let table = parse_checkpoints(MAINNET_CHECKPOINT_TABLE).unwrap();
+        let lines = MAINNET_CHECKPOINT_TABLE.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "# number,hash");
+        assert_eq!(table.len(), lines.len() - 1);
+        assert_eq!(table[0].number, 0);
+        assert!(table.windows(2).all(|pair| pair[0].number < pair[1].number));
+    }
+
+    #[test]
+    fn test_mainnet_checkpoints() {
+        let table = parse_checkpoints(MAINNET_CHECKPOINT_TABLE).unwrap();
+
+        // Entries shared with the well-known hashes agree with them.
+        let store = CheckpointStore::mainnet();
+        for (number, hash) in MAINNET_CHECKPOINTS {
+            assert_eq!(store.get(number), Some(parse_hash(hash).unwrap()));
+        }
+        assert_eq!(store.get(0), Some(table[0].hash));
+
+        assert!(matches!(
+            parse_checkpoints("# number,hash\n\n0,0x01\n"),
+            Err(CheckpointError::InvalidCheckpoint { line: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_verify_anchored_segment() {
+        let chain = create_test_chain(100, 10);
+        let mut store = CheckpointStore::new();
+        assert!(matches!(
+            store.verify_segment(&chain),
+            Err(CheckpointError::NotAnchored)
+        ));
+
+        // Anchored at the end.
+        let head = Checkpoint {
+            number: 109,
+            hash: parse_hash(&chain[9].block_hash).unwrap(),
+        };
+        store.insert(head).unwrap();
+        assert_eq!(store.verify_segment(&chain).unwrap(), head);
+
+        // Anchored through the parent of the first block.
+        let mut store = CheckpointStore::new();
+        let parent = Checkpoint {
+            number: 99,
+            hash: H256::zero(),
+        };
+        store.insert(parent).unwrap();
+        assert_eq!(store.verify_segment(&chain).unwrap(), parent);
+        assert!(matches!(
+            store.insert(Checkpoint {
+                number: 99,
+                hash: H256::repeat_byte(1),
+            }),
+            Err(CheckpointError::ConflictingCheckpoint { number: 99 })
+        ));
+    }
+
+    #[test]
+    fn test_verify_forged_segment() {
+        let chain = create_test_chain(100, 10);
+        let forged = create_test_chain_genesis(&zero_hash(), 100, 10, 1);
+        let mut store = CheckpointStore::new();
+        store
+            .insert(Checkpoint {
+                number: 109,
+                hash: parse_hash(&chain[9].block_hash).unwrap(),
+            })
+            .unwrap();
+
+        assert!(matches!(
+            store.verify_segment(&forged),
+            Err(CheckpointError::CheckpointMismatch { number: 109 })
+        ));
+
+        let mut spliced = chain[..5].to_vec();
+        spliced.extend_from_slice(&forged[5..]);
+        assert!(matches!(
+            store.verify_segment(&spliced),
+            Err(CheckpointError::BrokenLink { number: 105 })
+        ));
+        assert!(matches!(
+            store.verify_segment(&[]),
+            Err(CheckpointError::EmptySegment)
+        ));
+    }
+}
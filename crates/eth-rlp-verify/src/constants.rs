@@ -74,3 +74,66 @@ pub const SHAPELLA_END: u64 = 19_426_586;
 /// # Value
 /// - `19,426,587` is the block number where the Dencun upgrade begins.
 pub const DENCUN_START: u64 = 19_426_587;
+
+/// The last block number of the Dencun era.
+///
+/// This constant marks the last block of the Dencun era, which concludes just before the start of
+/// the Prague upgrade.
+///
+/// # Value
+/// - `22,431,083` is the block number marking the end of the Dencun era.
+pub const DENCUN_END: u64 = 22_431_083;
+
+/// The first block number of the Prague era.
+///
+/// The Prague era begins at block `22,431,084` with the Prague upgrade (Pectra on the consensus
+/// side), which adds the `requests_hash` field committing to the execution layer requests of the
+/// block (EIP-7685).
+///
+/// # Value
+/// - `22,431,084` is the block number where the Prague upgrade begins.
+pub const PRAGUE_START: u64 = 22_431_084;
+
+/// The number of beacon chain slots whose block roots are summarized together.
+///
+/// Every `SLOTS_PER_HISTORICAL_ROOT` slots, the beacon chain commits to the block roots of the
+/// period in a historical batch (before Capella) or a historical summary (from Capella onwards).
+///
+/// # Value
+/// - `8,192` slots, i.e. 256 epochs of 32 slots.
+pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8_192;
+
+/// The first beacon chain slot of the Bellatrix fork on mainnet.
+///
+/// Bellatrix introduced execution payloads in beacon blocks, ahead of the merge at block
+/// `PARIS_START`.
+///
+/// # Value
+/// - `4,636,672` is the first slot of epoch `144,896`.
+pub const BELLATRIX_START_SLOT: u64 = 4_636_672;
+
+/// The first beacon chain slot of the Capella fork on mainnet.
+///
+/// Capella is the consensus side of the Shapella upgrade. It replaced the growing list of
+/// `historical_roots` with `historical_summaries`, and added withdrawals to execution payloads.
+///
+/// # Value
+/// - `6,209,536` is the first slot of epoch `194,048`.
+pub const CAPELLA_START_SLOT: u64 = 6_209_536;
+
+/// The first beacon chain slot of the Deneb fork on mainnet.
+///
+/// Deneb is the consensus side of the Dencun upgrade, adding blob gas fields to execution payloads.
+///
+/// # Value
+/// - `8,626,176` is the first slot of epoch `269,568`.
+pub const DENEB_START_SLOT: u64 = 8_626_176;
+
+/// The first beacon chain slot of the Electra fork on mainnet.
+///
+/// Electra is the consensus side of the Prague upgrade, adding execution requests to beacon blocks
+/// and growing the beacon state.
+///
+/// # Value
+/// - `11,649,024` is the first slot of epoch `364,032`.
+pub const ELECTRA_START_SLOT: u64 = 11_649_024;
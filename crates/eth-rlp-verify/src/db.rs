@@ -0,0 +1,339 @@
+use crate::chain::{BlockResult, ChainVerifier};
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use futures_util::stream::BoxStream;
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use std::ops::RangeInclusive;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// The name of the table holding block headers, with one column per `BlockHeader` field.
+pub const BLOCK_HEADERS_TABLE: &str = "blockheaders";
+
+/// The name of the table verification results are written to.
+pub const RESULTS_TABLE: &str = "block_verification_results";
+
+/// The schema of `RESULTS_TABLE`, created by `verify_range_into_results` if missing.
+pub static RESULTS_TABLE_SCHEMA: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {RESULTS_TABLE} (
+    number bigint PRIMARY KEY,
+    block_hash character(66) NOT NULL,
+    hash_valid boolean NOT NULL,
+    linked boolean NOT NULL,
+    verified_at timestamp with time zone NOT NULL DEFAULT now()
+)"
+    )
+});
+
+/// The query of `stream_block_headers`. It lives as long as the returned stream.
+static SELECT_BLOCK_HEADERS: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "SELECT block_hash, number, gas_limit, gas_used, nonce, transaction_root, receipts_root,
+            state_root, base_fee_per_gas, parent_hash, ommers_hash, miner, logs_bloom, difficulty,
+            totaldifficulty, sha3_uncles, timestamp, extra_data, mix_hash, withdrawals_root,
+            blob_gas_used, excess_blob_gas, parent_beacon_block_root, requests_hash
+        FROM {BLOCK_HEADERS_TABLE}
+        WHERE number BETWEEN $1 AND $2
+        ORDER BY number"
+    )
+});
+
+/// The number of results written per `INSERT` statement.
+const RESULTS_BATCH_SIZE: usize = 1000;
+
+#[derive(Error, Debug)]
+pub enum DbError {
+    #[error("Database error: {0}")]
+    SqlxError(#[from] sqlx::Error),
+    #[error("Block number {0} does not fit in a bigint column")]
+    BlockNumberOverflow(u64),
+}
+
+/// The outcome of a verification job.
+///
+/// # Fields
+///
+/// - `verified_count`: The number of headers verified.
+/// - `failure_count`: The number of headers that failed a check.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct JobSummary {
+    pub verified_count: u64,
+    pub failure_count: u64,
+}
+
+fn to_bigint(number: u64) -> Result<i64, DbError> {
+    i64::try_from(number).map_err(|_| DbError::BlockNumberOverflow(number))
+}
+
+/// Streams a range of headers from `BLOCK_HEADERS_TABLE`, in ascending block number order.
+///
+/// Rows are decoded one at a time, so that ranges far larger than memory can be read.
+///
+/// # Arguments
+///
+/// - `pool`: The Postgres connection pool.
+/// - `range`: The block numbers to read.
+///
+/// # Returns
+///
+/// A `Result` containing a stream of headers, or an error if the range does not fit the table.
+pub fn stream_block_headers(
+    pool: &PgPool,
+    range: RangeInclusive<u64>,
+) -> Result<BoxStream<'_, Result<VerifiableBlockHeader, sqlx::Error>>, DbError> {
+    let (start, end) = (to_bigint(*range.start())?, to_bigint(*range.end())?);
+    Ok(
+        sqlx::query_as::<_, VerifiableBlockHeader>(&SELECT_BLOCK_HEADERS)
+            .bind(start)
+            .bind(end)
+            .fetch(pool),
+    )
+}
+
+/// Writes a batch of results, replacing the previous results of the same blocks.
+async fn write_results(pool: &PgPool, results: &[BlockResult]) -> Result<(), DbError> {
+    let numbers = results
+        .iter()
+        .map(|result| to_bigint(result.number))
+        .collect::<Result<Vec<_>, _>>()?;
+    let block_hashes: Vec<&str> = results
+        .iter()
+        .map(|result| result.block_hash.as_str())
+        .collect();
+    let hash_valid: Vec<bool> = results.iter().map(|result| result.hash_valid).collect();
+    let linked: Vec<bool> = results.iter().map(|result| result.linked).collect();
+
+    let query = format!(
+        "INSERT INTO {RESULTS_TABLE} (number, block_hash, hash_valid, linked)
+        SELECT * FROM UNNEST($1::bigint[], $2::text[], $3::boolean[], $4::boolean[])
+        ON CONFLICT (number) DO UPDATE SET
+            block_hash = EXCLUDED.block_hash,
+            hash_valid = EXCLUDED.hash_valid,
+            linked = EXCLUDED.linked,
+            verified_at = now()"
+    );
+    sqlx::query(&query)
+        .bind(numbers)
+        .bind(block_hashes)
+        .bind(hash_valid)
+        .bind(linked)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Verifies a range of headers from `BLOCK_HEADERS_TABLE` and writes one row per block into
+/// `RESULTS_TABLE`.
+///
+/// Headers are streamed through a `ChainVerifier`, so that both hashes and parent links are
+/// checked in constant memory. Results are written in batches, and running the job again over
+/// the same range replaces the previous results.
+///
+/// # Arguments
+///
+/// - `pool`: The Postgres connection pool.
+/// - `range`: The block numbers to verify.
+///
+/// # Returns
+///
+/// A `Result<JobSummary, DbError>` counting the verified headers and the failures.
+pub async fn verify_range_into_results(
+    pool: &PgPool,
+    range: RangeInclusive<u64>,
+) -> Result<JobSummary, DbError> {
+    sqlx::query(&RESULTS_TABLE_SCHEMA).execute(pool).await?;
+
+    let mut verifier = ChainVerifier::new();
+    let mut results = Vec::with_capacity(RESULTS_BATCH_SIZE);
+    let mut block_headers = stream_block_headers(pool, range)?;
+    while let Some(block_header) = block_headers.try_next().await? {
+        results.push(verifier.push(block_header));
+        if results.len() == RESULTS_BATCH_SIZE {
+            write_results(pool, &results).await?;
+            results.clear();
+        }
+    }
+    if !results.is_empty() {
+        write_results(pool, &results).await?;
+    }
+
+    Ok(JobSummary {
+        verified_count: verifier.verified_count(),
+        failure_count: verifier.failure_count(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::PRAGUE_START;
+    use crate::test_helpers::{create_test_chain, create_test_chain_prague};
+    use sqlx::postgres::PgConnectOptions;
+    use std::str::FromStr;
+
+    /// Connects to the Postgres instance at `DATABASE_URL` inside a fresh `schema`, so that tests
+    /// running concurrently do not share tables.
+    async fn test_pool(schema: &str) -> PgPool {
+        let url = std::env::var("DATABASE_URL").unwrap();
+        let admin = PgPool::connect(&url).await.unwrap();
+        for statement in [
+            format!("DROP SCHEMA IF EXISTS {schema} CASCADE"),
+            format!("CREATE SCHEMA {schema}"),
+        ] {
+            sqlx::query(&statement).execute(&admin).await.unwrap();
+        }
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema)]);
+        PgPool::connect_with(options).await.unwrap()
+    }
+
+    /// The schema of `BLOCK_HEADERS_TABLE`, which the `BlockHeader` struct mirrors.
+    const BLOCK_HEADERS_SCHEMA: &str = "CREATE TABLE {table} (
+        block_hash character(66) NOT NULL,
+        number bigint NOT NULL,
+        gas_limit bigint NOT NULL,
+        gas_used bigint NOT NULL,
+        nonce character varying(78) NOT NULL,
+        transaction_root character(66),
+        receipts_root character(66),
+        state_root character(66),
+        base_fee_per_gas character varying(78),
+        parent_hash character varying(66),
+        ommers_hash character varying(66),
+        miner character varying(42),
+        logs_bloom character varying(1024),
+        difficulty character varying(78),
+        totaldifficulty character varying(78),
+        sha3_uncles character varying(66),
+        timestamp character varying(100),
+        extra_data character varying(1024),
+        mix_hash character varying(66),
+        withdrawals_root character varying(66),
+        blob_gas_used character varying(78),
+        excess_blob_gas character varying(78),
+        parent_beacon_block_root character varying(66),
+        requests_hash character varying(66)
+    )";
+
+    async fn insert_headers(pool: &PgPool, block_headers: &[VerifiableBlockHeader]) {
+        sqlx::query(&BLOCK_HEADERS_SCHEMA.replace("{table}", BLOCK_HEADERS_TABLE))
+            .execute(pool)
+            .await
+            .unwrap();
+        let insert = format!(
+            "INSERT INTO {BLOCK_HEADERS_TABLE} VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
+                $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24)"
+        );
+        for h in block_headers {
+            sqlx::query(&insert)
+                .bind(&h.block_hash)
+                .bind(h.number)
+                .bind(h.gas_limit)
+                .bind(h.gas_used)
+                .bind(&h.nonce)
+                .bind(&h.transaction_root)
+                .bind(&h.receipts_root)
+                .bind(&h.state_root)
+                .bind(&h.base_fee_per_gas)
+                .bind(&h.parent_hash)
+                .bind(&h.ommers_hash)
+                .bind(&h.miner)
+                .bind(&h.logs_bloom)
+                .bind(&h.difficulty)
+                .bind(&h.totaldifficulty)
+                .bind(&h.sha3_uncles)
+                .bind(&h.timestamp)
+                .bind(&h.extra_data)
+                .bind(&h.mix_hash)
+                .bind(&h.withdrawals_root)
+                .bind(&h.blob_gas_used)
+                .bind(&h.excess_blob_gas)
+                .bind(&h.parent_beacon_block_root)
+                .bind(&h.requests_hash)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(0, 10)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a Postgres instance at DATABASE_URL"]
+    async fn test_stream_block_headers() {
+        let pool = test_pool("test_stream_block_headers").await;
+        let chain = chain();
+        insert_headers(&pool, &chain).await;
+
+        let block_headers: Vec<VerifiableBlockHeader> = stream_block_headers(&pool, 2..=5)
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(block_headers, chain[2..=5]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a Postgres instance at DATABASE_URL"]
+    async fn test_verify_range_into_results() {
+        let pool = test_pool("test_verify_range_into_results").await;
+        let mut chain = chain();
+        chain[4].gas_used += 1;
+        insert_headers(&pool, &chain).await;
+
+        let summary = verify_range_into_results(&pool, 0..=9).await.unwrap();
+        assert_eq!(
+            summary,
+            JobSummary {
+                verified_count: 10,
+                failure_count: 1
+            }
+        );
+
+        let failed: Vec<(i64, bool, bool)> = sqlx::query_as(&format!(
+            "SELECT number, hash_valid, linked FROM {RESULTS_TABLE}
+            WHERE NOT (hash_valid AND linked)"
+        ))
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(failed, vec![(4, false, true)]);
+
+        verify_range_into_results(&pool, 0..=9).await.unwrap();
+        let count: i64 = sqlx::query_scalar(&format!("SELECT count(*) FROM {RESULTS_TABLE}"))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 10);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a Postgres instance at DATABASE_URL"]
+    async fn test_verify_prague_range_into_results() {
+        let pool = test_pool("test_verify_prague_range_into_results").await;
+        let chain = create_test_chain_prague(3);
+        insert_headers(&pool, &chain).await;
+
+        let range = PRAGUE_START..=PRAGUE_START + 2;
+        let block_headers: Vec<VerifiableBlockHeader> = stream_block_headers(&pool, range.clone())
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(block_headers, chain);
+
+        let summary = verify_range_into_results(&pool, range).await.unwrap();
+        assert_eq!(summary.failure_count, 0);
+        let valid: Vec<bool> = sqlx::query_scalar(&format!(
+            "SELECT hash_valid FROM {RESULTS_TABLE} ORDER BY number"
+        ))
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(valid, vec![true; 3]);
+    }
+}
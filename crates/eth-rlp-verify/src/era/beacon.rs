@@ -0,0 +1,792 @@
+use super::e2store::{E2StoreReader, VERSION};
+use super::{decompress, EraError};
+use crate::beacon::BeaconFork;
+use crate::payload::{ExecutionPayload, PayloadError, Withdrawal};
+use primitive_types::{H160, H256, U256};
+use std::io::Read;
+
+/// The type of the entries holding a snappy-compressed SSZ `SignedBeaconBlock`.
+pub const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+/// The type of the entry holding the snappy-compressed SSZ `BeaconState` closing an era.
+pub const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+/// The type of the entries indexing the blocks and the state of an era.
+pub const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+/// The position of the `execution_payload` offset in a `BeaconBlockBody`, after the RANDAO
+/// reveal, the eth1 data, the graffiti, five operation list offsets and the sync aggregate.
+const PAYLOAD_OFFSET_POSITION: usize = 380;
+
+/// The position of the `execution_requests` offset in an Electra `BeaconBlockBody`.
+const REQUESTS_OFFSET_POSITION: usize = 392;
+
+/// The position of the `body` offset in a `BeaconBlock`, after the slot, the proposer index, the
+/// parent root and the state root.
+const BODY_OFFSET_POSITION: usize = 80;
+
+/// The position of the `extra_data` offset in an `ExecutionPayload`.
+const EXTRA_DATA_OFFSET_POSITION: usize = 436;
+
+/// The position of the `transactions` offset in an `ExecutionPayload`, followed by the
+/// `withdrawals` offset from Capella and by the blob gas fields from Deneb.
+const TRANSACTIONS_OFFSET_POSITION: usize = 504;
+
+/// The length of an SSZ `Withdrawal`: index, validator index, address and amount.
+const WITHDRAWAL_LENGTH: usize = 44;
+
+fn bytes(data: &[u8], start: usize, end: usize) -> Result<&[u8], EraError> {
+    if start > end {
+        return Err(EraError::InvalidSsz("offsets out of order"));
+    }
+    data.get(start..end)
+        .ok_or(EraError::InvalidSsz("data is too short"))
+}
+
+fn read_u64(data: &[u8], position: usize) -> Result<u64, EraError> {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(bytes(data, position, position + 8)?);
+    Ok(u64::from_le_bytes(word))
+}
+
+fn read_offset(data: &[u8], position: usize) -> Result<usize, EraError> {
+    let mut word = [0u8; 4];
+    word.copy_from_slice(bytes(data, position, position + 4)?);
+    Ok(u32::from_le_bytes(word) as usize)
+}
+
+fn hex_string(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+/// Splits an SSZ list of variable-size items, e.g. the transactions of a payload.
+fn split_list(data: &[u8]) -> Result<Vec<&[u8]>, EraError> {
+    if data.is_empty() {
+        return Ok(vec![]);
+    }
+    let first = read_offset(data, 0)?;
+    if first % 4 != 0 || first == 0 {
+        return Err(EraError::InvalidSsz("invalid list offset"));
+    }
+    let offsets = (0..first / 4)
+        .map(|index| read_offset(data, index * 4))
+        .chain(std::iter::once(Ok(data.len())))
+        .collect::<Result<Vec<_>, _>>()?;
+    offsets
+        .windows(2)
+        .map(|window| bytes(data, window[0], window[1]))
+        .collect()
+}
+
+/// An execution payload extracted from a beacon block.
+///
+/// # Fields
+///
+/// - `slot`: The slot of the beacon block.
+/// - `fork`: The beacon chain fork of the slot, which determines the payload layout.
+/// - `parent_beacon_block_root`: The parent root of the beacon block, committed to by Dencun and
+///   later execution headers.
+/// - `payload`: The execution payload.
+/// - `execution_requests`: The type prefixed execution requests of Electra blocks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EraPayload {
+    pub slot: u64,
+    pub fork: BeaconFork,
+    pub parent_beacon_block_root: H256,
+    pub payload: ExecutionPayload,
+    pub execution_requests: Option<Vec<String>>,
+}
+
+impl EraPayload {
+    /// Verifies the `block_hash` of the payload against the hash of the header it describes.
+    pub fn verify_block_hash(&self) -> Result<bool, PayloadError> {
+        let parent_beacon_block_root =
+            (self.fork >= BeaconFork::Deneb).then_some(self.parent_beacon_block_root);
+        self.payload
+            .verify_block_hash(parent_beacon_block_root, self.execution_requests.as_deref())
+    }
+}
+
+/// Decodes an SSZ execution payload of the given fork.
+fn decode_payload(data: &[u8], fork: BeaconFork) -> Result<ExecutionPayload, EraError> {
+    let h256 = |position: usize| -> Result<String, EraError> {
+        Ok(format!(
+            "{:?}",
+            H256::from_slice(bytes(data, position, position + 32)?)
+        ))
+    };
+    let quantity = |position: usize| -> Result<String, EraError> {
+        Ok(format!("{:#x}", read_u64(data, position)?))
+    };
+
+    let extra_data_offset = read_offset(data, EXTRA_DATA_OFFSET_POSITION)?;
+    let transactions_offset = read_offset(data, TRANSACTIONS_OFFSET_POSITION)?;
+    let (transactions_end, withdrawals) = if fork >= BeaconFork::Capella {
+        let withdrawals_offset = read_offset(data, TRANSACTIONS_OFFSET_POSITION + 4)?;
+        let withdrawals = bytes(data, withdrawals_offset, data.len())?;
+        if withdrawals.len() % WITHDRAWAL_LENGTH != 0 {
+            return Err(EraError::InvalidSsz("invalid withdrawals length"));
+        }
+        let withdrawals = withdrawals
+            .chunks(WITHDRAWAL_LENGTH)
+            .map(|withdrawal| {
+                Ok(Withdrawal {
+                    index: format!("{:#x}", read_u64(withdrawal, 0)?),
+                    validator_index: format!("{:#x}", read_u64(withdrawal, 8)?),
+                    address: format!("{:?}", H160::from_slice(&withdrawal[16..36])),
+                    amount: format!("{:#x}", read_u64(withdrawal, 36)?),
+                })
+            })
+            .collect::<Result<Vec<_>, EraError>>()?;
+        (withdrawals_offset, Some(withdrawals))
+    } else {
+        (data.len(), None)
+    };
+    let (blob_gas_used, excess_blob_gas) = if fork >= BeaconFork::Deneb {
+        (
+            Some(quantity(TRANSACTIONS_OFFSET_POSITION + 8)?),
+            Some(quantity(TRANSACTIONS_OFFSET_POSITION + 16)?),
+        )
+    } else {
+        (None, None)
+    };
+
+    Ok(ExecutionPayload {
+        parent_hash: h256(0)?,
+        fee_recipient: format!("{:?}", H160::from_slice(bytes(data, 32, 52)?)),
+        state_root: h256(52)?,
+        receipts_root: h256(84)?,
+        logs_bloom: hex_string(bytes(data, 116, 372)?),
+        prev_randao: h256(372)?,
+        block_number: quantity(404)?,
+        gas_limit: quantity(412)?,
+        gas_used: quantity(420)?,
+        timestamp: quantity(428)?,
+        extra_data: hex_string(bytes(data, extra_data_offset, transactions_offset)?),
+        base_fee_per_gas: format!("{:#x}", U256::from_little_endian(bytes(data, 440, 472)?)),
+        block_hash: h256(472)?,
+        transactions: split_list(bytes(data, transactions_offset, transactions_end)?)?
+            .into_iter()
+            .map(hex_string)
+            .collect(),
+        withdrawals,
+        blob_gas_used,
+        excess_blob_gas,
+    })
+}
+
+/// Decodes the SSZ `ExecutionRequests` of an Electra block into EIP-7685 requests, each being
+/// its request type followed by the SSZ encoding of the requests of that type. Types without
+/// requests are left out, as in Engine API payloads.
+fn decode_execution_requests(data: &[u8]) -> Result<Vec<String>, EraError> {
+    let offsets = [
+        read_offset(data, 0)?,
+        read_offset(data, 4)?,
+        read_offset(data, 8)?,
+        data.len(),
+    ];
+    offsets
+        .windows(2)
+        .enumerate()
+        .filter(|(_, window)| window[0] != window[1])
+        .map(|(request_type, window)| {
+            let mut request = vec![request_type as u8];
+            request.extend_from_slice(bytes(data, window[0], window[1])?);
+            Ok(hex_string(&request))
+        })
+        .collect()
+}
+
+/// Extracts the execution payload of an SSZ encoded `SignedBeaconBlock`.
+///
+/// # Returns
+///
+/// A `Result<Option<EraPayload>, EraError>` containing the payload, or `None` for blocks without
+/// one: blocks preceding Bellatrix, and Bellatrix blocks preceding the merge whose payload is
+/// empty.
+pub fn execution_payload_from_ssz(signed_block: &[u8]) -> Result<Option<EraPayload>, EraError> {
+    let block = bytes(
+        signed_block,
+        read_offset(signed_block, 0)?,
+        signed_block.len(),
+    )?;
+    let slot = read_u64(block, 0)?;
+    let Some(fork) = BeaconFork::at_slot(slot) else {
+        return Ok(None);
+    };
+    let parent_beacon_block_root = H256::from_slice(bytes(block, 16, 48)?);
+    let body = bytes(
+        block,
+        read_offset(block, BODY_OFFSET_POSITION)?,
+        block.len(),
+    )?;
+
+    let payload_start = read_offset(body, PAYLOAD_OFFSET_POSITION)?;
+    let payload_end = if fork >= BeaconFork::Capella {
+        read_offset(body, PAYLOAD_OFFSET_POSITION + 4)?
+    } else {
+        body.len()
+    };
+    let payload = decode_payload(bytes(body, payload_start, payload_end)?, fork)?;
+    if payload.block_hash == format!("{:?}", H256::zero()) {
+        return Ok(None);
+    }
+
+    let execution_requests = if fork >= BeaconFork::Electra {
+        let requests_start = read_offset(body, REQUESTS_OFFSET_POSITION)?;
+        Some(decode_execution_requests(bytes(
+            body,
+            requests_start,
+            body.len(),
+        )?)?)
+    } else {
+        None
+    };
+
+    Ok(Some(EraPayload {
+        slot,
+        fork,
+        parent_beacon_block_root,
+        payload,
+        execution_requests,
+    }))
+}
+
+/// Reads the execution payloads of the beacon blocks of an `.era` file.
+///
+/// An `.era` file holds the beacon blocks of one period of 8192 slots, followed by the beacon
+/// state at its end. Blocks without an execution payload are skipped, as are the state and the
+/// slot indices.
+pub struct EraReader<R> {
+    entries: E2StoreReader<R>,
+    started: bool,
+    failed: bool,
+}
+
+impl<R: Read> EraReader<R> {
+    /// Creates a reader over an `.era` file.
+    pub fn new(reader: R) -> Self {
+        EraReader {
+            entries: E2StoreReader::new(reader),
+            started: false,
+            failed: false,
+        }
+    }
+
+    fn read_next(&mut self) -> Result<Option<EraPayload>, EraError> {
+        if !self.started {
+            match self.entries.read_entry()? {
+                Some(entry) if entry.entry_type == VERSION => self.started = true,
+                Some(entry) => return Err(EraError::UnexpectedEntry(entry.entry_type)),
+                None => return Err(EraError::MissingEntry(VERSION)),
+            }
+        }
+
+        while let Some(entry) = self.entries.read_entry()? {
+            if entry.entry_type == COMPRESSED_SIGNED_BEACON_BLOCK {
+                if let Some(payload) = execution_payload_from_ssz(&decompress(&entry.data)?)? {
+                    return Ok(Some(payload));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for EraReader<R> {
+    type Item = Result<EraPayload, EraError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let payload = self.read_next().transpose();
+        self.failed = matches!(payload, Some(Err(_)));
+        payload
+    }
+}
+
+/// A problem found in an `.era` file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EraMismatch {
+    /// The block hash of the payload does not match the header it describes.
+    BlockHash { slot: u64, block_hash: String },
+    /// The parent hash of the payload is not the block hash of the previous payload.
+    ParentHash { slot: u64, block_hash: String },
+}
+
+/// The outcome of verifying an `.era` file.
+///
+/// # Fields
+///
+/// - `payload_count`: The number of execution payloads read.
+/// - `mismatches`: The problems found, in file order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EraReport {
+    pub payload_count: u64,
+    pub mismatches: Vec<EraMismatch>,
+}
+
+impl EraReport {
+    /// Returns whether no problem was found.
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Verifies every execution payload of an `.era` file.
+///
+/// Each payload is converted into a Paris, Shapella, Dencun or Prague header depending on its
+/// fork, and the hash of that header must match the payload's `block_hash`. Payloads must also
+/// link to the previous one, as slots without a block carry no payload.
+///
+/// # Returns
+///
+/// A `Result<EraReport, EraError>` listing the mismatches found.
+pub fn verify_era<R: Read>(reader: R) -> Result<EraReport, EraError> {
+    let mut report = EraReport::default();
+    let mut previous_hash: Option<String> = None;
+    for payload in EraReader::new(reader) {
+        let payload = payload?;
+        let slot = payload.slot;
+        let block_hash = payload.payload.block_hash.clone();
+
+        if !payload.verify_block_hash()? {
+            report.mismatches.push(EraMismatch::BlockHash {
+                slot,
+                block_hash: block_hash.clone(),
+            });
+        }
+        if previous_hash
+            .as_ref()
+            .is_some_and(|previous_hash| *previous_hash != payload.payload.parent_hash)
+        {
+            report.mismatches.push(EraMismatch::ParentHash {
+                slot,
+                block_hash: block_hash.clone(),
+            });
+        }
+        previous_hash = Some(block_hash);
+        report.payload_count += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{
+        BELLATRIX_START_SLOT, CAPELLA_START_SLOT, DENEB_START_SLOT, ELECTRA_START_SLOT,
+    };
+    use crate::era::compress;
+    use crate::era::e2store::E2StoreWriter;
+    use crate::payload::decode_hex;
+
+    /// The size of an SSZ offset, which stands for a variable size field in the fixed part of a
+    /// container.
+    const OFFSET: usize = 4;
+
+    /// The fields of a `BeaconBlockBody` and their sizes, as defined by the consensus specs.
+    fn body_fields(fork: BeaconFork) -> Vec<(&'static str, usize)> {
+        let mut fields = vec![
+            ("randao_reveal", 96),
+            ("eth1_data", 72),
+            ("graffiti", 32),
+            ("proposer_slashings", OFFSET),
+            ("attester_slashings", OFFSET),
+            ("attestations", OFFSET),
+            ("deposits", OFFSET),
+            ("voluntary_exits", OFFSET),
+            ("sync_aggregate", 160),
+            ("execution_payload", OFFSET),
+        ];
+        if fork >= BeaconFork::Capella {
+            fields.push(("bls_to_execution_changes", OFFSET));
+        }
+        if fork >= BeaconFork::Deneb {
+            fields.push(("blob_kzg_commitments", OFFSET));
+        }
+        if fork >= BeaconFork::Electra {
+            fields.push(("execution_requests", OFFSET));
+        }
+        fields
+    }
+
+    /// The fields of an `ExecutionPayload` and their sizes, as defined by the consensus specs.
+    fn payload_fields(fork: BeaconFork) -> Vec<(&'static str, usize)> {
+        let mut fields = vec![
+            ("parent_hash", 32),
+            ("fee_recipient", 20),
+            ("state_root", 32),
+            ("receipts_root", 32),
+            ("logs_bloom", 256),
+            ("prev_randao", 32),
+            ("block_number", 8),
+            ("gas_limit", 8),
+            ("gas_used", 8),
+            ("timestamp", 8),
+            ("extra_data", OFFSET),
+            ("base_fee_per_gas", 32),
+            ("block_hash", 32),
+            ("transactions", OFFSET),
+        ];
+        if fork >= BeaconFork::Capella {
+            fields.push(("withdrawals", OFFSET));
+        }
+        if fork >= BeaconFork::Deneb {
+            fields.extend([("blob_gas_used", 8), ("excess_blob_gas", 8)]);
+        }
+        fields
+    }
+
+    /// Returns the position of `field` in the fixed part of a container with the given fields.
+    fn position(fields: &[(&str, usize)], field: &str) -> usize {
+        fields
+            .iter()
+            .take_while(|(name, _)| *name != field)
+            .map(|(_, size)| size)
+            .sum()
+    }
+
+    /// Returns the length of the fixed part of a container with the given fields.
+    fn fixed_length(fields: &[(&str, usize)]) -> usize {
+        fields.iter().map(|(_, size)| size).sum()
+    }
+
+    fn offset(value: usize) -> [u8; 4] {
+        (value as u32).to_le_bytes()
+    }
+
+    fn ssz_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let mut encoded = vec![];
+        let mut position = items.len() * 4;
+        for item in items {
+            encoded.extend(offset(position));
+            position += item.len();
+        }
+        for item in items {
+            encoded.extend(item);
+        }
+        encoded
+    }
+
+    /// Encodes a payload with the layout of the given fork.
+    fn encode_payload(payload: &ExecutionPayload, fork: BeaconFork) -> Vec<u8> {
+        let quantity = |value: &str| u64::from_str_radix(&value[2..], 16).unwrap().to_le_bytes();
+        let fixed_length = fixed_length(&payload_fields(fork));
+        let extra_data = decode_hex(&payload.extra_data).unwrap();
+        let transactions: Vec<Vec<u8>> = payload
+            .transactions
+            .iter()
+            .map(|transaction| decode_hex(transaction).unwrap())
+            .collect();
+        let transactions = ssz_list(&transactions);
+
+        let mut encoded = vec![];
+        encoded.extend(decode_hex(&payload.parent_hash).unwrap());
+        encoded.extend(decode_hex(&payload.fee_recipient).unwrap());
+        encoded.extend(decode_hex(&payload.state_root).unwrap());
+        encoded.extend(decode_hex(&payload.receipts_root).unwrap());
+        encoded.extend(decode_hex(&payload.logs_bloom).unwrap());
+        encoded.extend(decode_hex(&payload.prev_randao).unwrap());
+        encoded.extend(quantity(&payload.block_number));
+        encoded.extend(quantity(&payload.gas_limit));
+        encoded.extend(quantity(&payload.gas_used));
+        encoded.extend(quantity(&payload.timestamp));
+        encoded.extend(offset(fixed_length));
+        encoded.extend(
+            U256::from(u64::from_le_bytes(quantity(&payload.base_fee_per_gas))).to_little_endian(),
+        );
+        encoded.extend(decode_hex(&payload.block_hash).unwrap());
+        encoded.extend(offset(fixed_length + extra_data.len()));
+        if fork >= BeaconFork::Capella {
+            encoded.extend(offset(fixed_length + extra_data.len() + transactions.len()));
+        }
+        if fork >= BeaconFork::Deneb {
+            encoded.extend(quantity(payload.blob_gas_used.as_deref().unwrap()));
+            encoded.extend(quantity(payload.excess_blob_gas.as_deref().unwrap()));
+        }
+        encoded.extend(extra_data);
+        encoded.extend(transactions);
+        for withdrawal in payload.withdrawals.iter().flatten() {
+            encoded.extend(quantity(&withdrawal.index));
+            encoded.extend(quantity(&withdrawal.validator_index));
+            encoded.extend(decode_hex(&withdrawal.address).unwrap());
+            encoded.extend(quantity(&withdrawal.amount));
+        }
+        encoded
+    }
+
+    /// Encodes a signed beacon block holding a payload, with empty operations.
+    fn encode_signed_block(
+        slot: u64,
+        parent_root: H256,
+        payload: &ExecutionPayload,
+        requests: &[Vec<u8>; 3],
+    ) -> Vec<u8> {
+        let fork = BeaconFork::at_slot(slot).unwrap();
+        let payload = encode_payload(payload, fork);
+        let fixed_length = fixed_length(&body_fields(fork));
+
+        let mut body = vec![0u8; 200];
+        body.extend(offset(fixed_length).repeat(5));
+        body.extend([0u8; 160]);
+        body.extend(offset(fixed_length));
+        let payload_end = fixed_length + payload.len();
+        if fork >= BeaconFork::Capella {
+            body.extend(offset(payload_end));
+        }
+        if fork >= BeaconFork::Deneb {
+            body.extend(offset(payload_end));
+        }
+        if fork >= BeaconFork::Electra {
+            body.extend(offset(payload_end));
+        }
+        body.extend(payload);
+        if fork >= BeaconFork::Electra {
+            body.extend(offset(12));
+            body.extend(offset(12 + requests[0].len()));
+            body.extend(offset(12 + requests[0].len() + requests[1].len()));
+            body.extend(requests.concat());
+        }
+
+        let mut block = slot.to_le_bytes().to_vec();
+        block.extend(7u64.to_le_bytes());
+        block.extend(parent_root.as_bytes());
+        block.extend([0u8; 32]);
+        block.extend(offset(84));
+        block.extend(body);
+
+        let mut signed_block = offset(100).to_vec();
+        signed_block.extend([0u8; 96]);
+        signed_block.extend(block);
+        signed_block
+    }
+
+    fn mock_payload(number: u64, parent_hash: H256, fork: BeaconFork) -> ExecutionPayload {
+        ExecutionPayload {
+            parent_hash: format!("{parent_hash:?}"),
+            fee_recipient: format!("{:?}", H160::repeat_byte(0x22)),
+            state_root: format!("{:?}", H256::repeat_byte(0x33)),
+            receipts_root: format!("{:?}", H256::repeat_byte(0x44)),
+            logs_bloom: format!("0x{}", "00".repeat(256)),
+            prev_randao: format!("{:?}", H256::repeat_byte(0x55)),
+            block_number: format!("{number:#x}"),
+            gas_limit: "0x1c9c380".to_string(),
+            gas_used: "0x5208".to_string(),
+            timestamp: format!("{:#x}", 1_700_000_000 + number * 12),
+            extra_data: "0x6265617665726275696c642e6f7267".to_string(),
+            base_fee_per_gas: "0x7".to_string(),
+            block_hash: format!("{:?}", H256::zero()),
+            transactions: vec![
+                "0x02f86b0180843b9aca00850c92a69c0082520894000000000000000000000000000000000000000080c0".to_string(),
+                "0xaabb".to_string(),
+            ],
+            withdrawals: (fork >= BeaconFork::Capella).then(|| {
+                vec![Withdrawal {
+                    index: "0x1".to_string(),
+                    validator_index: "0x2a".to_string(),
+                    address: format!("{:?}", H160::repeat_byte(0x66)),
+                    amount: "0xf4240".to_string(),
+                }]
+            }),
+            blob_gas_used: (fork >= BeaconFork::Deneb).then(|| "0x20000".to_string()),
+            excess_blob_gas: (fork >= BeaconFork::Deneb).then(|| "0x0".to_string()),
+        }
+    }
+
+    /// Writes an `.era` file holding a chain of blocks, one per slot from `first_slot`.
+    fn write_era(
+        first_slot: u64,
+        length: u64,
+        requests: &[Vec<u8>; 3],
+    ) -> (Vec<u8>, Vec<EraPayload>) {
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(VERSION, &[]).unwrap();
+        let mut payloads: Vec<EraPayload> = vec![];
+        for slot in first_slot..first_slot + length {
+            let fork = BeaconFork::at_slot(slot).unwrap();
+            let parent_hash = payloads
+                .last()
+                .map(|previous| {
+                    H256::from_slice(&decode_hex(&previous.payload.block_hash).unwrap())
+                })
+                .unwrap_or_default();
+            let mut payload = EraPayload {
+                slot,
+                fork,
+                parent_beacon_block_root: H256::from_low_u64_be(slot),
+                payload: mock_payload(20_000_000 + slot - first_slot, parent_hash, fork),
+                execution_requests: (fork >= BeaconFork::Electra).then(|| {
+                    requests
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, data)| !data.is_empty())
+                        .map(|(request_type, data)| {
+                            let mut request = vec![request_type as u8];
+                            request.extend(data);
+                            hex_string(&request)
+                        })
+                        .collect()
+                }),
+            };
+            let parent_beacon_block_root =
+                (fork >= BeaconFork::Deneb).then_some(payload.parent_beacon_block_root);
+            payload.payload.block_hash = format!(
+                "{:?}",
+                payload
+                    .payload
+                    .compute_block_hash(
+                        parent_beacon_block_root,
+                        payload.execution_requests.as_deref()
+                    )
+                    .unwrap()
+            );
+
+            let signed_block = encode_signed_block(
+                slot,
+                payload.parent_beacon_block_root,
+                &payload.payload,
+                requests,
+            );
+            writer
+                .write_entry(
+                    COMPRESSED_SIGNED_BEACON_BLOCK,
+                    &compress(&signed_block).unwrap(),
+                )
+                .unwrap();
+            payloads.push(payload);
+        }
+        writer
+            .write_entry(COMPRESSED_BEACON_STATE, &compress(&[0; 16]).unwrap())
+            .unwrap();
+        writer.write_entry(SLOT_INDEX, &[0; 24]).unwrap();
+        (writer.into_inner(), payloads)
+    }
+
+    #[test]
+    fn test_ssz_layout() {
+        let block_fields = [
+            ("slot", 8),
+            ("proposer_index", 8),
+            ("parent_root", 32),
+            ("state_root", 32),
+            ("body", OFFSET),
+        ];
+        assert_eq!(position(&block_fields, "body"), BODY_OFFSET_POSITION);
+
+        for fork in [
+            BeaconFork::Bellatrix,
+            BeaconFork::Capella,
+            BeaconFork::Deneb,
+            BeaconFork::Electra,
+        ] {
+            let body = body_fields(fork);
+            assert_eq!(
+                position(&body, "execution_payload"),
+                PAYLOAD_OFFSET_POSITION
+            );
+            if fork >= BeaconFork::Capella {
+                assert_eq!(
+                    position(&body, "bls_to_execution_changes"),
+                    PAYLOAD_OFFSET_POSITION + 4
+                );
+            }
+            if fork >= BeaconFork::Electra {
+                assert_eq!(
+                    position(&body, "execution_requests"),
+                    REQUESTS_OFFSET_POSITION
+                );
+            }
+
+            let payload = payload_fields(fork);
+            assert_eq!(position(&payload, "extra_data"), EXTRA_DATA_OFFSET_POSITION);
+            assert_eq!(
+                position(&payload, "transactions"),
+                TRANSACTIONS_OFFSET_POSITION
+            );
+            if fork >= BeaconFork::Capella {
+                assert_eq!(
+                    position(&payload, "withdrawals"),
+                    TRANSACTIONS_OFFSET_POSITION + 4
+                );
+            }
+            if fork >= BeaconFork::Deneb {
+                assert_eq!(
+                    position(&payload, "blob_gas_used"),
+                    TRANSACTIONS_OFFSET_POSITION + 8
+                );
+                assert_eq!(
+                    position(&payload, "excess_blob_gas"),
+                    TRANSACTIONS_OFFSET_POSITION + 16
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_extract_payloads() {
+        let requests = [vec![0xaa; 8], vec![], vec![0xbb; 4]];
+        for first_slot in [
+            CAPELLA_START_SLOT - 1,
+            DENEB_START_SLOT - 1,
+            ELECTRA_START_SLOT - 1,
+        ] {
+            let (bytes, payloads) = write_era(first_slot, 3, &requests);
+            let read: Vec<EraPayload> = EraReader::new(&bytes[..])
+                .collect::<Result<_, _>>()
+                .unwrap();
+            assert_eq!(read, payloads);
+
+            let report = verify_era(&bytes[..]).unwrap();
+            assert!(report.is_valid());
+            assert_eq!(report.payload_count, 3);
+        }
+    }
+
+    #[test]
+    fn test_era_mismatches() {
+        let requests = [vec![], vec![], vec![]];
+        let mut payload = mock_payload(1, H256::zero(), BeaconFork::Deneb);
+        let empty = ExecutionPayload {
+            block_hash: format!("{:?}", H256::zero()),
+            ..mock_payload(0, H256::zero(), BeaconFork::Bellatrix)
+        };
+        payload.block_hash = format!("{:?}", H256::repeat_byte(1));
+
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(VERSION, &[]).unwrap();
+        for (slot, payload) in [
+            (BELLATRIX_START_SLOT, &empty),
+            (DENEB_START_SLOT, &payload),
+            (DENEB_START_SLOT + 1, &payload),
+        ] {
+            let signed_block = encode_signed_block(slot, H256::zero(), payload, &requests);
+            writer
+                .write_entry(
+                    COMPRESSED_SIGNED_BEACON_BLOCK,
+                    &compress(&signed_block).unwrap(),
+                )
+                .unwrap();
+        }
+
+        let report = verify_era(&writer.into_inner()[..]).unwrap();
+        let block_hash = payload.block_hash.clone();
+        assert_eq!(report.payload_count, 2);
+        assert_eq!(
+            report.mismatches,
+            vec![
+                EraMismatch::BlockHash {
+                    slot: DENEB_START_SLOT,
+                    block_hash: block_hash.clone()
+                },
+                EraMismatch::BlockHash {
+                    slot: DENEB_START_SLOT + 1,
+                    block_hash: block_hash.clone()
+                },
+                EraMismatch::ParentHash {
+                    slot: DENEB_START_SLOT + 1,
+                    block_hash
+                },
+            ]
+        );
+    }
+}
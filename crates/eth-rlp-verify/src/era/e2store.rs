@@ -0,0 +1,161 @@
+use super::EraError;
+use std::io::{ErrorKind, Read, Write};
+
+/// The type of the `Version` entry opening every e2store file.
+pub const VERSION: [u8; 2] = [0x65, 0x32];
+
+/// The length of the header preceding the data of every entry.
+const HEADER_LENGTH: usize = 8;
+
+/// A single e2store entry.
+///
+/// # Fields
+///
+/// - `entry_type`: The two type bytes of the entry.
+/// - `data`: The data of the entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub entry_type: [u8; 2],
+    pub data: Vec<u8>,
+}
+
+/// Reads the entries of an e2store file, the container format of `.era` and `.era1` files.
+///
+/// Each entry is an 8-byte header, made of the type, the little-endian `u32` data length and two
+/// reserved zero bytes, followed by the data.
+pub struct E2StoreReader<R> {
+    reader: R,
+    position: u64,
+    failed: bool,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    /// Creates a reader over the entries of `reader`, starting at its current position.
+    pub fn new(reader: R) -> Self {
+        E2StoreReader {
+            reader,
+            position: 0,
+            failed: false,
+        }
+    }
+
+    /// Returns the offset of the next entry, relative to where the reader started.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Reads the next entry, or returns `None` at the end of the file.
+    pub fn read_entry(&mut self) -> Result<Option<Entry>, EraError> {
+        let mut header = [0u8; HEADER_LENGTH];
+        let mut filled = 0;
+        while filled < HEADER_LENGTH {
+            match self.reader.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(EraError::TruncatedEntry),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        if header[6..] != [0, 0] {
+            return Err(EraError::InvalidEntryHeader);
+        }
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        let mut data = vec![0; length];
+        self.reader.read_exact(&mut data).map_err(|error| {
+            if error.kind() == ErrorKind::UnexpectedEof {
+                EraError::TruncatedEntry
+            } else {
+                error.into()
+            }
+        })?;
+        self.position += (HEADER_LENGTH + length) as u64;
+
+        Ok(Some(Entry {
+            entry_type: [header[0], header[1]],
+            data,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for E2StoreReader<R> {
+    type Item = Result<Entry, EraError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let entry = self.read_entry().transpose();
+        self.failed = matches!(entry, Some(Err(_)));
+        entry
+    }
+}
+
+/// Writes e2store entries.
+pub struct E2StoreWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    /// Creates a writer appending entries to `writer`.
+    pub fn new(writer: W) -> Self {
+        E2StoreWriter { writer }
+    }
+
+    /// Writes an entry.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<u64, EraError>` containing the number of bytes written, header included.
+    pub fn write_entry(&mut self, entry_type: [u8; 2], data: &[u8]) -> Result<u64, EraError> {
+        let length = u32::try_from(data.len()).map_err(|_| EraError::EntryTooLarge(data.len()))?;
+        self.writer.write_all(&entry_type)?;
+        self.writer.write_all(&length.to_le_bytes())?;
+        self.writer.write_all(&[0, 0])?;
+        self.writer.write_all(data)?;
+        Ok((HEADER_LENGTH + data.len()) as u64)
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_e2store_roundtrip() {
+        let mut writer = E2StoreWriter::new(vec![]);
+        assert_eq!(writer.write_entry(VERSION, &[]).unwrap(), 8);
+        writer.write_entry([0x03, 0x00], &[1, 2, 3]).unwrap();
+        let bytes = writer.into_inner();
+        assert_eq!(&bytes[..8], &[0x65, 0x32, 0, 0, 0, 0, 0, 0]);
+
+        let entries: Vec<Entry> = E2StoreReader::new(&bytes[..])
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    entry_type: VERSION,
+                    data: vec![]
+                },
+                Entry {
+                    entry_type: [0x03, 0x00],
+                    data: vec![1, 2, 3]
+                },
+            ]
+        );
+
+        let mut reader = E2StoreReader::new(&bytes[..bytes.len() - 1]);
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.position(), 8);
+        assert!(matches!(reader.next(), Some(Err(EraError::TruncatedEntry))));
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,498 @@
+use super::e2store::{E2StoreReader, Entry, VERSION};
+use super::{decompress, EraError};
+use crate::accumulator::{EpochAccumulator, HeaderRecord};
+use crate::constants::LONDON_START;
+use crate::eras::{BlockHeaderGenesis, BlockHeaderLondon};
+use crate::proofs::keccak256;
+use eth_rlp_types::{BlockHeaderError, BlockHeaderTrait};
+use primitive_types::{H256, U256};
+use std::io::Read;
+
+/// The type of the entries holding a snappy-compressed RLP block header.
+pub const COMPRESSED_HEADER: [u8; 2] = [0x03, 0x00];
+/// The type of the entries holding a snappy-compressed RLP block body.
+pub const COMPRESSED_BODY: [u8; 2] = [0x04, 0x00];
+/// The type of the entries holding the snappy-compressed RLP receipts of a block.
+pub const COMPRESSED_RECEIPTS: [u8; 2] = [0x05, 0x00];
+/// The type of the entries holding the little-endian total difficulty of a block.
+pub const TOTAL_DIFFICULTY: [u8; 2] = [0x06, 0x00];
+/// The type of the entry holding the root of the epoch accumulator of the file.
+pub const ACCUMULATOR: [u8; 2] = [0x07, 0x00];
+/// The type of the entry indexing the blocks of the file.
+pub const BLOCK_INDEX: [u8; 2] = [0x66, 0x32];
+
+/// A block read from an Era1 file.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `hash`: The Keccak256 hash of the RLP encoded header.
+/// - `header`: The RLP encoded header.
+/// - `total_difficulty`: The total difficulty of the chain up to and including the block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Era1Block {
+    pub number: u64,
+    pub hash: H256,
+    pub header: Vec<u8>,
+    pub total_difficulty: U256,
+}
+
+/// The block index closing an Era1 file.
+///
+/// # Fields
+///
+/// - `starting_number`: The number of the first block of the file.
+/// - `offsets`: The offset of each block tuple, relative to the start of the index entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockIndex {
+    pub starting_number: u64,
+    pub offsets: Vec<i64>,
+}
+
+impl BlockIndex {
+    /// Decodes a block index: the starting number, one offset per block and the block count, all
+    /// as little-endian 64-bit integers.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, EraError> {
+        let words: Vec<[u8; 8]> = data
+            .chunks_exact(8)
+            .map(|word| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(word);
+                bytes
+            })
+            .collect();
+        let (Some(first), Some(last)) = (words.first(), words.last()) else {
+            return Err(EraError::InvalidIndex);
+        };
+        let count = u64::from_le_bytes(*last) as usize;
+        if !data.len().is_multiple_of(8) || words.len().checked_sub(2) != Some(count) {
+            return Err(EraError::InvalidIndex);
+        }
+
+        Ok(BlockIndex {
+            starting_number: u64::from_le_bytes(*first),
+            offsets: words[1..=count]
+                .iter()
+                .map(|word| i64::from_le_bytes(*word))
+                .collect(),
+        })
+    }
+}
+
+/// Reads the blocks of an Era1 file, the archive format of pre-merge history.
+///
+/// An Era1 file holds the blocks of one accumulator epoch of up to 8192 blocks. Each block is a
+/// tuple of compressed header, body and receipts entries followed by its total difficulty. The
+/// file ends with the root of the epoch accumulator and a block index, which are available from
+/// `accumulator_root` and `block_index` once every block has been read. The positions of the
+/// header entries and of the index are recorded, so that the offsets of the index can be checked.
+pub struct Era1Reader<R> {
+    entries: E2StoreReader<R>,
+    started: bool,
+    failed: bool,
+    accumulator_root: Option<H256>,
+    block_index: Option<BlockIndex>,
+    header_positions: Vec<u64>,
+    index_position: Option<u64>,
+}
+
+impl<R: Read> Era1Reader<R> {
+    /// Creates a reader over an Era1 file.
+    pub fn new(reader: R) -> Self {
+        Era1Reader {
+            entries: E2StoreReader::new(reader),
+            started: false,
+            failed: false,
+            accumulator_root: None,
+            block_index: None,
+            header_positions: vec![],
+            index_position: None,
+        }
+    }
+
+    /// Returns the root of the epoch accumulator, once read.
+    pub fn accumulator_root(&self) -> Option<H256> {
+        self.accumulator_root
+    }
+
+    /// Returns the block index, once read.
+    pub fn block_index(&self) -> Option<&BlockIndex> {
+        self.block_index.as_ref()
+    }
+
+    /// Returns the position of the `COMPRESSED_HEADER` entry of every block read so far, from the
+    /// start of the file.
+    pub fn header_positions(&self) -> &[u64] {
+        &self.header_positions
+    }
+
+    /// Returns the position of the block index entry from the start of the file, once read.
+    pub fn index_position(&self) -> Option<u64> {
+        self.index_position
+    }
+
+    /// Reads the next entry, which must be of the given type.
+    fn expect_entry(&mut self, entry_type: [u8; 2]) -> Result<Entry, EraError> {
+        match self.entries.read_entry()? {
+            Some(entry) if entry.entry_type == entry_type => Ok(entry),
+            Some(entry) => Err(EraError::UnexpectedEntry(entry.entry_type)),
+            None => Err(EraError::TruncatedEntry),
+        }
+    }
+
+    /// Reads the rest of a block tuple, after its compressed header.
+    fn read_block(&mut self, header: Entry) -> Result<Era1Block, EraError> {
+        let header = decompress(&header.data)?;
+        self.expect_entry(COMPRESSED_BODY)?;
+        self.expect_entry(COMPRESSED_RECEIPTS)?;
+        let total_difficulty = self.expect_entry(TOTAL_DIFFICULTY)?;
+        if total_difficulty.data.len() != 32 {
+            return Err(EraError::InvalidEntryLength {
+                expected: 32,
+                got: total_difficulty.data.len(),
+            });
+        }
+
+        let number = rlp::Rlp::new(&header)
+            .val_at::<U256>(8)
+            .map_err(BlockHeaderError::from)?;
+        Ok(Era1Block {
+            number: u64::try_from(number).map_err(|_| EraError::InvalidBlockNumber)?,
+            hash: keccak256(&header),
+            header,
+            total_difficulty: U256::from_little_endian(&total_difficulty.data),
+        })
+    }
+
+    fn read_next(&mut self) -> Result<Option<Era1Block>, EraError> {
+        if !self.started {
+            self.expect_entry(VERSION)?;
+            self.started = true;
+        }
+
+        loop {
+            let position = self.entries.position();
+            let Some(entry) = self.entries.read_entry()? else {
+                return Ok(None);
+            };
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    self.header_positions.push(position);
+                    return self.read_block(entry).map(Some);
+                }
+                ACCUMULATOR if entry.data.len() == 32 => {
+                    self.accumulator_root = Some(H256::from_slice(&entry.data));
+                }
+                ACCUMULATOR => {
+                    return Err(EraError::InvalidEntryLength {
+                        expected: 32,
+                        got: entry.data.len(),
+                    })
+                }
+                BLOCK_INDEX => {
+                    self.block_index = Some(BlockIndex::from_bytes(&entry.data)?);
+                    self.index_position = Some(position);
+                }
+                // Other entry types, e.g. e2store padding, carry no block data.
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Era1Reader<R> {
+    type Item = Result<Era1Block, EraError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let block = self.read_next().transpose();
+        self.failed = matches!(block, Some(Err(_)));
+        block
+    }
+}
+
+/// A problem found in an Era1 file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Era1Mismatch {
+    /// The decoded header hashes to something else than the stored header, i.e. the header is
+    /// not a valid header of its era.
+    HeaderHash { number: u64 },
+    /// The block does not follow the previous block.
+    NonConsecutiveBlock { number: u64 },
+    /// The parent hash of the block is not the hash of the previous block.
+    ParentHash { number: u64 },
+    /// The total difficulty is not the previous total difficulty plus the block difficulty.
+    TotalDifficulty { number: u64 },
+    /// The accumulator root stored in the file does not match its blocks.
+    AccumulatorRoot { expected: H256, computed: H256 },
+    /// The block index does not match the blocks of the file.
+    BlockIndex,
+    /// The block index offset of the block does not point at its `COMPRESSED_HEADER` entry.
+    BlockOffset { number: u64 },
+}
+
+/// The outcome of verifying an Era1 file.
+///
+/// # Fields
+///
+/// - `first_block`: The number of the first block, if any.
+/// - `block_count`: The number of blocks read.
+/// - `mismatches`: The problems found, in file order.
+/// - `epoch_accumulator`: The accumulator built from the blocks of the file, which can be checked
+///   against a trusted `PreMergeAccumulator` with `verify_epoch`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Era1Report {
+    pub first_block: Option<u64>,
+    pub block_count: u64,
+    pub mismatches: Vec<Era1Mismatch>,
+    pub epoch_accumulator: EpochAccumulator,
+}
+
+impl Era1Report {
+    /// Returns whether no problem was found.
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Decodes a pre-merge header with the decoder of its era.
+///
+/// # Returns
+///
+/// The hash, parent hash and difficulty of the decoded header.
+fn decode_pre_merge_header(number: u64, header: &[u8]) -> Result<(H256, H256, U256), EraError> {
+    if number < LONDON_START {
+        let header = BlockHeaderGenesis::rlp_decode(header)?;
+        Ok((header.compute_hash(), header.parent_hash, header.difficulty))
+    } else {
+        let header = BlockHeaderLondon::rlp_decode(header)?;
+        Ok((header.compute_hash(), header.parent_hash, header.difficulty))
+    }
+}
+
+/// Verifies every block of an Era1 file.
+///
+/// Each header is decoded with the Genesis or London decoder and its hash recomputed, blocks must
+/// be consecutive and linked, and total difficulties must add up. The epoch accumulator is then
+/// rebuilt from the block hashes and total difficulties and compared with the root stored in the
+/// file, and every offset of the block index must point at the header entry of its block.
+///
+/// # Arguments
+///
+/// - `reader`: The content of the Era1 file.
+///
+/// # Returns
+///
+/// A `Result<Era1Report, EraError>` listing the mismatches found, or an error if the file cannot
+/// be read or a header cannot be decoded.
+pub fn verify_era1<R: Read>(reader: R) -> Result<Era1Report, EraError> {
+    let mut reader = Era1Reader::new(reader);
+    let mut report = Era1Report::default();
+    let mut previous: Option<(u64, H256, U256)> = None;
+
+    for block in &mut reader {
+        let block = block?;
+        let number = block.number;
+        let (hash, parent_hash, difficulty) = decode_pre_merge_header(number, &block.header)?;
+        if hash != block.hash {
+            report.mismatches.push(Era1Mismatch::HeaderHash { number });
+        }
+
+        if let Some((previous_number, previous_hash, previous_total_difficulty)) = previous {
+            if number != previous_number + 1 {
+                report
+                    .mismatches
+                    .push(Era1Mismatch::NonConsecutiveBlock { number });
+            }
+            if parent_hash != previous_hash {
+                report.mismatches.push(Era1Mismatch::ParentHash { number });
+            }
+            if block.total_difficulty != previous_total_difficulty + difficulty {
+                report
+                    .mismatches
+                    .push(Era1Mismatch::TotalDifficulty { number });
+            }
+        }
+        previous = Some((number, block.hash, block.total_difficulty));
+
+        report.first_block.get_or_insert(number);
+        report.block_count += 1;
+        report.epoch_accumulator.records.push(HeaderRecord {
+            block_hash: block.hash,
+            total_difficulty: block.total_difficulty,
+        });
+    }
+
+    let expected = reader
+        .accumulator_root()
+        .ok_or(EraError::MissingEntry(ACCUMULATOR))?;
+    let computed = report.epoch_accumulator.tree_hash_root();
+    if expected != computed {
+        report
+            .mismatches
+            .push(Era1Mismatch::AccumulatorRoot { expected, computed });
+    }
+
+    let block_index = reader
+        .block_index()
+        .ok_or(EraError::MissingEntry(BLOCK_INDEX))?;
+    if report
+        .first_block
+        .is_some_and(|first| first != block_index.starting_number)
+        || block_index.offsets.len() as u64 != report.block_count
+    {
+        report.mismatches.push(Era1Mismatch::BlockIndex);
+    }
+
+    let index_position = reader.index_position().unwrap_or_default() as i128;
+    for (index, (offset, position)) in block_index
+        .offsets
+        .iter()
+        .zip(reader.header_positions())
+        .enumerate()
+    {
+        if index_position + *offset as i128 != *position as i128 {
+            report.mismatches.push(Era1Mismatch::BlockOffset {
+                number: block_index.starting_number + index as u64,
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::compress;
+    use crate::era::e2store::E2StoreWriter;
+    use crate::test_helpers::create_test_chain;
+    use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+
+    /// Writes an Era1 file holding `block_headers`, with the given total difficulties.
+    fn write_era1(block_headers: &[VerifiableBlockHeader], total_difficulties: &[U256]) -> Vec<u8> {
+        let mut writer = E2StoreWriter::new(vec![]);
+        let mut position = writer.write_entry(VERSION, &[]).unwrap();
+        let mut positions = vec![];
+        let mut records = vec![];
+        for (block_header, total_difficulty) in block_headers.iter().zip(total_difficulties) {
+            let header = BlockHeaderGenesis::from_db_header(block_header.clone()).unwrap();
+            positions.push(position);
+            records.push(HeaderRecord {
+                block_hash: header.compute_hash(),
+                total_difficulty: *total_difficulty,
+            });
+
+            let entries = [
+                (COMPRESSED_HEADER, compress(&header.rlp_encode()).unwrap()),
+                (COMPRESSED_BODY, compress(&[0xc2, 0xc0, 0xc0]).unwrap()),
+                (COMPRESSED_RECEIPTS, compress(&[0xc0]).unwrap()),
+                (
+                    TOTAL_DIFFICULTY,
+                    total_difficulty.to_little_endian().to_vec(),
+                ),
+            ];
+            for (entry_type, data) in entries {
+                position += writer.write_entry(entry_type, &data).unwrap();
+            }
+        }
+
+        let root = EpochAccumulator { records }.tree_hash_root();
+        position += writer.write_entry(ACCUMULATOR, root.as_bytes()).unwrap();
+
+        let mut index = (block_headers[0].number as u64).to_le_bytes().to_vec();
+        for block_position in positions {
+            index.extend((block_position as i64 - position as i64).to_le_bytes());
+        }
+        index.extend((block_headers.len() as u64).to_le_bytes());
+        writer.write_entry(BLOCK_INDEX, &index).unwrap();
+        writer.into_inner()
+    }
+
+    fn chain() -> (Vec<VerifiableBlockHeader>, Vec<U256>) {
+        let chain = create_test_chain(0, 5);
+        let total_difficulties = (1..=5u64)
+            .map(|count| U256::from(0x400000000u64) * count)
+            .collect();
+        (chain, total_difficulties)
+    }
+
+    #[test]
+    fn test_read_era1() {
+        let (chain, total_difficulties) = chain();
+        let bytes = write_era1(&chain, &total_difficulties);
+
+        let mut reader = Era1Reader::new(&bytes[..]);
+        let blocks: Vec<Era1Block> = (&mut reader).collect::<Result<_, _>>().unwrap();
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(format!("{:?}", blocks[3].hash), chain[3].block_hash);
+        assert_eq!(blocks[3].total_difficulty, total_difficulties[3]);
+        assert_eq!(reader.block_index().unwrap().offsets.len(), 5);
+
+        let report = verify_era1(&bytes[..]).unwrap();
+        assert!(report.is_valid());
+        assert_eq!((report.first_block, report.block_count), (Some(0), 5));
+    }
+
+    #[test]
+    fn test_era1_mismatches() {
+        let (mut chain, mut total_difficulties) = chain();
+        total_difficulties[2] += U256::one();
+        chain[4].parent_hash = Some(chain[2].block_hash.clone());
+        let bytes = write_era1(&chain, &total_difficulties);
+
+        let report = verify_era1(&bytes[..]).unwrap();
+        assert_eq!(
+            report.mismatches,
+            vec![
+                Era1Mismatch::TotalDifficulty { number: 2 },
+                Era1Mismatch::TotalDifficulty { number: 3 },
+                Era1Mismatch::ParentHash { number: 4 },
+            ]
+        );
+
+        assert!(matches!(
+            verify_era1(&bytes[8..]),
+            Err(EraError::UnexpectedEntry(COMPRESSED_HEADER))
+        ));
+    }
+
+    #[test]
+    fn test_era1_block_offsets() {
+        let (chain, total_difficulties) = chain();
+        let mut bytes = write_era1(&chain, &total_difficulties);
+
+        let mut reader = Era1Reader::new(&bytes[..]);
+        (&mut reader).for_each(drop);
+        let index_position = reader.index_position().unwrap();
+        for (offset, position) in reader
+            .block_index()
+            .unwrap()
+            .offsets
+            .iter()
+            .zip(reader.header_positions())
+        {
+            assert_eq!(index_position as i64 + offset, *position as i64);
+        }
+
+        // The index ends with one offset per block and the block count.
+        let length = bytes.len();
+        let offset = |number: usize| length - 8 * (1 + chain.len() - number);
+        let (first, second) = (offset(1), offset(3));
+        for index in 0..8 {
+            bytes.swap(first + index, second + index);
+        }
+        bytes[offset(4)] ^= 1;
+
+        let report = verify_era1(&bytes[..]).unwrap();
+        assert_eq!(
+            report.mismatches,
+            vec![
+                Era1Mismatch::BlockOffset { number: 1 },
+                Era1Mismatch::BlockOffset { number: 3 },
+                Era1Mismatch::BlockOffset { number: 4 },
+            ]
+        );
+    }
+}
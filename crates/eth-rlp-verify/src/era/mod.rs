@@ -0,0 +1,67 @@
+mod beacon;
+mod e2store;
+mod era1;
+
+use crate::payload::PayloadError;
+use eth_rlp_types::BlockHeaderError;
+use std::io::Read;
+use thiserror::Error;
+
+pub use beacon::{
+    execution_payload_from_ssz, verify_era, EraMismatch, EraPayload, EraReader, EraReport,
+    COMPRESSED_BEACON_STATE, COMPRESSED_SIGNED_BEACON_BLOCK, SLOT_INDEX,
+};
+pub use e2store::{E2StoreReader, E2StoreWriter, Entry, VERSION};
+pub use era1::{
+    verify_era1, BlockIndex, Era1Block, Era1Mismatch, Era1Reader, Era1Report, ACCUMULATOR,
+    BLOCK_INDEX, COMPRESSED_BODY, COMPRESSED_HEADER, COMPRESSED_RECEIPTS, TOTAL_DIFFICULTY,
+};
+
+#[derive(Error, Debug)]
+pub enum EraError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Entry is truncated")]
+    TruncatedEntry,
+    #[error("Entry header has non-zero reserved bytes")]
+    InvalidEntryHeader,
+    #[error("Entry of {0} bytes is too large")]
+    EntryTooLarge(usize),
+    #[error("Unexpected entry of type {0:02x?}")]
+    UnexpectedEntry([u8; 2]),
+    #[error("Missing entry of type {0:02x?}")]
+    MissingEntry([u8; 2]),
+    #[error("Invalid entry length: expected {expected}, got {got}")]
+    InvalidEntryLength { expected: usize, got: usize },
+    #[error("Invalid index")]
+    InvalidIndex,
+    #[error("Block number does not fit in 64 bits")]
+    InvalidBlockNumber,
+    #[error("Snappy decompression error: {0}")]
+    DecompressionError(std::io::Error),
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+    #[error("Invalid SSZ: {0}")]
+    InvalidSsz(&'static str),
+    #[error("Payload error: {0}")]
+    PayloadError(#[from] PayloadError),
+}
+
+/// Decompresses the data of an entry, compressed with the snappy framing format.
+pub(crate) fn decompress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(data)
+        .read_to_end(&mut decompressed)
+        .map_err(EraError::DecompressionError)?;
+    Ok(decompressed)
+}
+
+/// Compresses data with the snappy framing format.
+#[cfg(test)]
+pub(crate) fn compress(data: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    use std::io::Write;
+
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.into_inner().map_err(|error| error.into_error())
+}
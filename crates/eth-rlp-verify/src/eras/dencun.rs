@@ -114,28 +114,29 @@ impl BlockHeaderDencun {
     pub fn into_verifiable(self) -> VerifiableBlockHeader {
         VerifiableBlockHeader {
             block_hash: "".to_string(), // Placeholder; compute if necessary.
-            parent_hash: Some(self.parent_hash.to_string()),
-            ommers_hash: Some(self.ommers_hash.to_string()),
-            miner: Some(self.beneficiary.to_string()),
-            state_root: Some(self.state_root.to_string()),
-            transaction_root: Some(self.transactions_root.to_string()),
-            receipts_root: Some(self.receipts_root.to_string()),
-            logs_bloom: Some(hex::encode(self.logs_bloom)),
-            difficulty: Some(self.difficulty.to_string()),
+            parent_hash: Some(format!("{:?}", self.parent_hash)),
+            ommers_hash: Some(format!("{:?}", self.ommers_hash)),
+            miner: Some(format!("{:?}", self.beneficiary)),
+            state_root: Some(format!("{:?}", self.state_root)),
+            transaction_root: Some(format!("{:?}", self.transactions_root)),
+            receipts_root: Some(format!("{:?}", self.receipts_root)),
+            logs_bloom: Some(format!("0x{}", hex::encode(self.logs_bloom))),
+            difficulty: Some(format!("{:#x}", self.difficulty)),
             totaldifficulty: None, // Not available in Dencun.
             number: self.number.as_u64() as i64,
             gas_limit: self.gas_limit.as_u64() as i64,
             gas_used: self.gas_used.as_u64() as i64,
-            timestamp: Some(self.timestamp.to_string()),
-            extra_data: Some(hex::encode(self.extra_data)),
-            mix_hash: Some(self.mix_hash.to_string()),
-            nonce: hex::encode(self.nonce),
-            base_fee_per_gas: Some(self.base_fee_per_gas.to_string()),
-            withdrawals_root: Some(self.withdrawals_root.to_string()),
-            parent_beacon_block_root: Some(self.parent_beacon_block_root.to_string()),
-            blob_gas_used: Some(self.blob_gas_used.to_string()),
-            excess_blob_gas: Some(self.excess_blob_gas.to_string()),
-            sha3_uncles: None, // Not applicable for Dencun.
+            timestamp: Some(format!("{:#x}", self.timestamp)),
+            extra_data: Some(format!("0x{}", hex::encode(self.extra_data))),
+            mix_hash: Some(format!("{:?}", self.mix_hash)),
+            nonce: format!("0x{}", hex::encode(self.nonce)),
+            base_fee_per_gas: Some(format!("{:#x}", self.base_fee_per_gas)),
+            withdrawals_root: Some(format!("{:?}", self.withdrawals_root)),
+            parent_beacon_block_root: Some(format!("{:?}", self.parent_beacon_block_root)),
+            blob_gas_used: Some(format!("{:#x}", self.blob_gas_used)),
+            excess_blob_gas: Some(format!("{:#x}", self.excess_blob_gas)),
+            sha3_uncles: Some(format!("{:?}", self.ommers_hash)),
+            requests_hash: None,
         }
     }
 }
@@ -2,11 +2,12 @@ mod dencun;
 mod genesis;
 mod london;
 mod paris;
+mod prague;
 mod shapella;
 
 use crate::constants::{
-    DENCUN_START, GENESIS_END, LONDON_END, LONDON_START, PARIS_END, PARIS_START, SHAPELLA_END,
-    SHAPELLA_START,
+    DENCUN_END, DENCUN_START, GENESIS_END, LONDON_END, LONDON_START, PARIS_END, PARIS_START,
+    PRAGUE_START, SHAPELLA_END, SHAPELLA_START,
 };
 use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError, BlockHeaderTrait};
 
@@ -15,8 +16,17 @@ pub use dencun::verify_hash_dencun;
 pub use genesis::verify_hash_genesis;
 pub use london::verify_hash_london;
 pub use paris::verify_hash_paris;
+pub use prague::verify_hash_prague;
 pub use shapella::verify_hash_shapella;
 
+// Re-export each era's block header type so that headers can be hashed and encoded directly.
+pub use dencun::BlockHeaderDencun;
+pub use genesis::BlockHeaderGenesis;
+pub use london::BlockHeaderLondon;
+pub use paris::BlockHeaderParis;
+pub use prague::BlockHeaderPrague;
+pub use shapella::BlockHeaderShapella;
+
 type DecoderFn = fn(&[u8]) -> Result<VerifiableBlockHeader, BlockHeaderError>;
 type HashVerifierFn = fn(String, VerifiableBlockHeader) -> Result<bool, BlockHeaderError>;
 type EncoderFn = fn(VerifiableBlockHeader) -> Result<Vec<u8>, BlockHeaderError>;
@@ -45,7 +55,8 @@ type EncoderFn = fn(VerifiableBlockHeader) -> Result<Vec<u8>, BlockHeaderError>;
 /// - **London:** The era between `LONDON_START` and `LONDON_END`, inclusive. This upgrade introduced EIP-1559, changing the gas fee model.
 /// - **Paris:** The era between `PARIS_START` and `PARIS_END`, representing Ethereum's merge from proof-of-work (PoW) to proof-of-stake (PoS).
 /// - **Shapella:** The era between `SHAPELLA_START` and `SHAPELLA_END`, introducing further changes to Ethereum's staking and withdrawal mechanisms.
-/// - **Dencun:** Blocks from `DENCUN_START` onwards, which represent the upcoming Dencun upgrade.
+/// - **Dencun:** The era between `DENCUN_START` and `DENCUN_END`, adding blob gas fields and the parent beacon block root.
+/// - **Prague:** Blocks from `PRAGUE_START` onwards, adding the execution requests hash.
 /// - **Genesis:** The initial blocks from block 0 up to `GENESIS_END`, covering Ethereum's early history.
 ///
 /// # Notes
@@ -58,8 +69,10 @@ pub fn determine_era(block_number: u64) -> Option<HashVerifierFn> {
         Some(verify_hash_paris)
     } else if (SHAPELLA_START..=SHAPELLA_END).contains(&block_number) {
         Some(verify_hash_shapella)
-    } else if block_number >= DENCUN_START {
+    } else if (DENCUN_START..=DENCUN_END).contains(&block_number) {
         Some(verify_hash_dencun)
+    } else if block_number >= PRAGUE_START {
+        Some(verify_hash_prague)
     } else if block_number <= GENESIS_END {
         Some(verify_hash_genesis)
     } else {
@@ -74,8 +87,10 @@ pub fn determine_era_encoder(block_number: u64) -> Option<EncoderFn> {
         Some(|header| Ok(paris::BlockHeaderParis::from_db_header(header)?.rlp_encode()))
     } else if (SHAPELLA_START..=SHAPELLA_END).contains(&block_number) {
         Some(|header| Ok(shapella::BlockHeaderShapella::from_db_header(header)?.rlp_encode()))
-    } else if block_number >= DENCUN_START {
+    } else if (DENCUN_START..=DENCUN_END).contains(&block_number) {
         Some(|header| Ok(dencun::BlockHeaderDencun::from_db_header(header)?.rlp_encode()))
+    } else if block_number >= PRAGUE_START {
+        Some(|header| Ok(prague::BlockHeaderPrague::from_db_header(header)?.rlp_encode()))
     } else if block_number <= GENESIS_END {
         Some(|header| Ok(genesis::BlockHeaderGenesis::from_db_header(header)?.rlp_encode()))
     } else {
@@ -92,8 +107,10 @@ pub fn determine_era_decoder(block_number: u64) -> Option<DecoderFn> {
         Some(|data| paris::BlockHeaderParis::rlp_decode(data).map(|h| h.into_verifiable()))
     } else if (SHAPELLA_START..=SHAPELLA_END).contains(&block_number) {
         Some(|data| shapella::BlockHeaderShapella::rlp_decode(data).map(|h| h.into_verifiable()))
-    } else if block_number >= DENCUN_START {
+    } else if (DENCUN_START..=DENCUN_END).contains(&block_number) {
         Some(|data| dencun::BlockHeaderDencun::rlp_decode(data).map(|h| h.into_verifiable()))
+    } else if block_number >= PRAGUE_START {
+        Some(|data| prague::BlockHeaderPrague::rlp_decode(data).map(|h| h.into_verifiable()))
     } else {
         None
     }
@@ -0,0 +1,271 @@
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError, BlockHeaderTrait};
+use primitive_types::{H160, H256, U256};
+use rlp::{Rlp, RlpStream};
+use std::str::FromStr;
+
+/// Represents the block header for the Prague upgrade in Ethereum.
+///
+/// The Prague upgrade adds the `requests_hash` field (EIP-7685), committing to the execution layer
+/// requests (deposits, withdrawals and consolidations) produced by the block.
+///
+/// # Fields
+///
+/// - `parent_hash`: The hash of the parent block, linking this block to the blockchain.
+/// - `ommers_hash`: The hash of the ommers (uncles) included in this block.
+/// - `beneficiary`: The Ethereum address of the fee recipient of the block.
+/// - `state_root`: The root of the state trie after the block is processed.
+/// - `transactions_root`: The Merkle root of the transactions included in the block.
+/// - `receipts_root`: The root of the transaction receipts for transactions in the block.
+/// - `logs_bloom`: A bloom filter used for efficient searching and filtering of logs, 256 bytes in size.
+/// - `difficulty`: The difficulty of the block, always zero after the merge.
+/// - `number`: The block number, which indicates its position in the blockchain.
+/// - `gas_limit`: The maximum gas allowed to be consumed by transactions in this block.
+/// - `gas_used`: The actual gas used by the transactions in this block.
+/// - `timestamp`: The time when the block was produced.
+/// - `extra_data`: Arbitrary data added by the block producer, up to 32 bytes in length.
+/// - `mix_hash`: The `prev_randao` value of the beacon chain.
+/// - `nonce`: A 64-bit nonce, always zero after the merge.
+/// - `base_fee_per_gas`: The base gas fee per unit for transactions in this block, part of EIP-1559.
+/// - `withdrawals_root`: The Merkle root of the withdrawals processed in the block.
+/// - `blob_gas_used`: The gas used for blob-related transactions.
+/// - `excess_blob_gas`: The excess blob gas in the block.
+/// - `parent_beacon_block_root`: The root of the parent beacon block.
+/// - `requests_hash`: The commitment to the execution layer requests of the block (new in Prague).
+#[derive(Debug, PartialEq)]
+pub struct BlockHeaderPrague {
+    pub parent_hash: H256,
+    pub ommers_hash: H256,
+    pub beneficiary: H160,
+    pub state_root: H256,
+    pub transactions_root: H256,
+    pub receipts_root: H256,
+    pub logs_bloom: [u8; 256],
+    pub difficulty: U256,
+    pub number: U256,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub timestamp: U256,
+    pub extra_data: Vec<u8>,
+    pub mix_hash: H256,
+    pub nonce: [u8; 8],
+    pub base_fee_per_gas: U256,
+    pub withdrawals_root: H256,
+    pub parent_beacon_block_root: H256,
+    pub blob_gas_used: U256,
+    pub excess_blob_gas: U256,
+    pub requests_hash: H256, // New in Prague
+}
+
+impl BlockHeaderPrague {
+    /// Converts a `VerifiableBlockHeader` from the database into a `BlockHeaderPrague`.
+    ///
+    /// # Arguments
+    ///
+    /// - `db_header`: A `VerifiableBlockHeader` fetched from the database, containing the raw string data
+    ///   for the block header fields.
+    ///
+    /// # Returns
+    ///
+    /// A `BlockHeaderPrague` instance populated with the parsed block header data, or an error if a
+    /// field, including `requests_hash`, is missing or malformed.
+    pub fn from_db_header(db_header: VerifiableBlockHeader) -> Result<Self, BlockHeaderError> {
+        let logs_bloom = <Self as BlockHeaderTrait>::hex_to_fixed_array::<256>(
+            &db_header.logs_bloom.unwrap_or_default(),
+        );
+        let nonce = <Self as BlockHeaderTrait>::hex_to_fixed_array::<8>("0x0000000000000000");
+
+        Ok(BlockHeaderPrague {
+            parent_hash: H256::from_str(&db_header.parent_hash.unwrap_or_default())?,
+            ommers_hash: H256::from_str(&db_header.sha3_uncles.unwrap_or_default())?,
+            beneficiary: H160::from_str(&db_header.miner.unwrap_or_default())?,
+            state_root: H256::from_str(&db_header.state_root.unwrap_or_default())?,
+            transactions_root: H256::from_str(&db_header.transaction_root.unwrap_or_default())?,
+            receipts_root: H256::from_str(&db_header.receipts_root.unwrap_or_default())?,
+            logs_bloom: logs_bloom?,
+            difficulty: U256::from_str(&db_header.difficulty.unwrap_or_default())?,
+            number: U256::from(db_header.number as u64),
+            gas_limit: U256::from(db_header.gas_limit as u64),
+            gas_used: U256::from(db_header.gas_used as u64),
+            timestamp: U256::from_str(&db_header.timestamp.unwrap_or_default())?,
+            extra_data: hex::decode(
+                db_header
+                    .extra_data
+                    .unwrap_or_default()
+                    .trim_start_matches("0x"),
+            )?,
+            mix_hash: H256::from_str(&db_header.mix_hash.unwrap_or_default())?,
+            nonce: nonce?,
+            base_fee_per_gas: U256::from_str(&db_header.base_fee_per_gas.unwrap_or_default())?,
+            withdrawals_root: H256::from_str(&db_header.withdrawals_root.unwrap_or_default())?,
+            parent_beacon_block_root: H256::from_str(
+                &db_header.parent_beacon_block_root.unwrap_or_default(),
+            )?,
+            blob_gas_used: U256::from_str(&db_header.blob_gas_used.unwrap_or_default())?,
+            excess_blob_gas: U256::from_str(&db_header.excess_blob_gas.unwrap_or_default())?,
+            requests_hash: H256::from_str(&db_header.requests_hash.unwrap_or_default())?,
+        })
+    }
+
+    /// Converts a `BlockHeaderPrague` into a common `BlockHeader`.
+    pub fn into_verifiable(self) -> VerifiableBlockHeader {
+        VerifiableBlockHeader {
+            block_hash: "".to_string(), // Placeholder; compute if necessary.
+            parent_hash: Some(format!("{:?}", self.parent_hash)),
+            ommers_hash: Some(format!("{:?}", self.ommers_hash)),
+            miner: Some(format!("{:?}", self.beneficiary)),
+            state_root: Some(format!("{:?}", self.state_root)),
+            transaction_root: Some(format!("{:?}", self.transactions_root)),
+            receipts_root: Some(format!("{:?}", self.receipts_root)),
+            logs_bloom: Some(format!("0x{}", hex::encode(self.logs_bloom))),
+            difficulty: Some(format!("{:#x}", self.difficulty)),
+            totaldifficulty: None, // Not available in Prague.
+            number: self.number.as_u64() as i64,
+            gas_limit: self.gas_limit.as_u64() as i64,
+            gas_used: self.gas_used.as_u64() as i64,
+            timestamp: Some(format!("{:#x}", self.timestamp)),
+            extra_data: Some(format!("0x{}", hex::encode(self.extra_data))),
+            mix_hash: Some(format!("{:?}", self.mix_hash)),
+            nonce: format!("0x{}", hex::encode(self.nonce)),
+            base_fee_per_gas: Some(format!("{:#x}", self.base_fee_per_gas)),
+            withdrawals_root: Some(format!("{:?}", self.withdrawals_root)),
+            parent_beacon_block_root: Some(format!("{:?}", self.parent_beacon_block_root)),
+            blob_gas_used: Some(format!("{:#x}", self.blob_gas_used)),
+            excess_blob_gas: Some(format!("{:#x}", self.excess_blob_gas)),
+            sha3_uncles: Some(format!("{:?}", self.ommers_hash)),
+            requests_hash: Some(format!("{:?}", self.requests_hash)),
+        }
+    }
+}
+
+/// Implements the `BlockHeaderTrait` for `BlockHeaderPrague`.
+impl BlockHeaderTrait for BlockHeaderPrague {
+    /// RLP encodes the Prague block header, returning a vector of bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<u8>` containing the RLP-encoded block header data.
+    fn rlp_encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(21); // 21 fields in Prague block header
+        stream.append(&self.parent_hash);
+        stream.append(&self.ommers_hash);
+        stream.append(&self.beneficiary);
+        stream.append(&self.state_root);
+        stream.append(&self.transactions_root);
+        stream.append(&self.receipts_root);
+        stream.append(&self.logs_bloom.to_vec());
+        stream.append(&self.difficulty);
+        stream.append(&self.number);
+        stream.append(&self.gas_limit);
+        stream.append(&self.gas_used);
+        stream.append(&self.timestamp);
+        stream.append(&self.extra_data);
+        stream.append(&self.mix_hash);
+        stream.append(&self.nonce.as_slice());
+        stream.append(&self.base_fee_per_gas);
+        stream.append(&self.withdrawals_root);
+        stream.append(&self.blob_gas_used);
+        stream.append(&self.excess_blob_gas);
+        stream.append(&self.parent_beacon_block_root);
+        stream.append(&self.requests_hash);
+        stream.out().to_vec()
+    }
+
+    /// Decodes an RLP-encoded byte slice into a `BlockHeaderPrague`.
+    ///
+    /// # Arguments
+    /// - `data`: A byte slice containing the RLP-encoded data.
+    ///
+    /// # Returns
+    /// - A `Result<Self>` containing the decoded block header or an error if decoding fails.
+    fn rlp_decode(data: &[u8]) -> Result<Self, BlockHeaderError> {
+        let rlp = Rlp::new(data);
+        Ok(BlockHeaderPrague {
+            parent_hash: rlp.val_at(0)?,
+            ommers_hash: rlp.val_at(1)?,
+            beneficiary: rlp.val_at(2)?,
+            state_root: rlp.val_at(3)?,
+            transactions_root: rlp.val_at(4)?,
+            receipts_root: rlp.val_at(5)?,
+            logs_bloom: rlp
+                .val_at::<Vec<u8>>(6)?
+                .try_into()
+                .map_err(|_| BlockHeaderError::InvalidLogsBloom)?,
+            difficulty: rlp.val_at(7)?,
+            number: rlp.val_at(8)?,
+            gas_limit: rlp.val_at(9)?,
+            gas_used: rlp.val_at(10)?,
+            timestamp: rlp.val_at(11)?,
+            extra_data: rlp.val_at(12)?,
+            mix_hash: rlp.val_at(13)?,
+            nonce: rlp
+                .val_at::<Vec<u8>>(14)?
+                .try_into()
+                .map_err(|_| BlockHeaderError::InvalidNonceSize)?,
+            base_fee_per_gas: rlp.val_at(15)?,
+            withdrawals_root: rlp.val_at(16)?,
+            blob_gas_used: rlp.val_at(17)?,
+            excess_blob_gas: rlp.val_at(18)?,
+            parent_beacon_block_root: rlp.val_at(19)?,
+            requests_hash: rlp.val_at(20)?,
+        })
+    }
+}
+
+/// Verifies the hash of a Prague block header.
+///
+/// # Arguments
+///
+/// - `block_hash`: A string containing the expected hash of the block.
+/// - `db_header`: A `VerifiableBlockHeader` fetched from the database, containing raw block header data.
+///
+/// # Returns
+///
+/// A boolean indicating whether the computed block hash matches the provided block hash.
+pub fn verify_hash_prague(
+    block_hash: String,
+    db_header: VerifiableBlockHeader,
+) -> Result<bool, BlockHeaderError> {
+    let header = BlockHeaderPrague::from_db_header(db_header)?;
+
+    // Compute the block hash
+    let computed_block_hash = header.compute_hash();
+
+    // Check if the computed hash matches the given block hash
+    Ok(computed_block_hash
+        == H256::from_str(&block_hash).map_err(BlockHeaderError::RustcHexDecodingError)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_prague() {
+        let header = BlockHeaderPrague {
+            parent_hash: H256::repeat_byte(1),
+            ommers_hash: H256::repeat_byte(2),
+            beneficiary: H160::repeat_byte(3),
+            state_root: H256::repeat_byte(4),
+            transactions_root: H256::repeat_byte(5),
+            receipts_root: H256::repeat_byte(6),
+            logs_bloom: [0; 256],
+            difficulty: U256::zero(),
+            number: U256::from(22_431_084),
+            gas_limit: U256::from(36_000_000),
+            gas_used: U256::from(21_000),
+            timestamp: U256::from(1_746_612_311),
+            extra_data: vec![0xab],
+            mix_hash: H256::repeat_byte(7),
+            nonce: [0; 8],
+            base_fee_per_gas: U256::from(7),
+            withdrawals_root: H256::repeat_byte(8),
+            parent_beacon_block_root: H256::repeat_byte(9),
+            blob_gas_used: U256::zero(),
+            excess_blob_gas: U256::zero(),
+            requests_hash: H256::repeat_byte(10),
+        };
+        let encoded = header.rlp_encode();
+        let decoded = BlockHeaderPrague::rlp_decode(&encoded).unwrap();
+        assert_eq!(header, decoded);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::chain::{follows, BlockResult};
+use crate::decode_block_header;
+use crate::proofs::keccak256;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::ops::{Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The name of the freezer table holding RLP encoded headers.
+pub const HEADERS_TABLE: &str = "headers";
+
+/// The name of the freezer table holding block hashes.
+pub const HASHES_TABLE: &str = "hashes";
+
+/// The length of an index entry: a big-endian `u16` file number and a big-endian `u32` offset.
+const INDEX_ENTRY_LENGTH: usize = 6;
+
+#[derive(Error, Debug)]
+pub enum FreezerError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Table {0} has no index file")]
+    MissingTable(String),
+    #[error("Invalid index file {0}")]
+    InvalidIndex(String),
+    #[error("Item {number} is not in the table")]
+    ItemNotFound { number: u64 },
+    #[error("Item {number} is truncated")]
+    TruncatedItem { number: u64 },
+    #[error("Snappy decompression error: {0}")]
+    DecompressionError(#[from] snap::Error),
+    #[error("Stored hash of block {number} is not 32 bytes long")]
+    InvalidHash { number: u64 },
+    #[error("Header of block {number} cannot be decoded")]
+    UndecodableHeader { number: u64 },
+}
+
+/// An index entry, pointing at the end of an item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct IndexEntry {
+    file_number: u32,
+    offset: u32,
+}
+
+/// A table of the geth freezer, the append-only store holding ancient chain data.
+///
+/// A table is made of an index file, `<name>.cidx` for snappy-compressed tables and
+/// `<name>.ridx` for raw ones, and of data files `<name>.0000.cdat`, `<name>.0001.cdat`, etc.
+/// Each 6-byte index entry holds the data file and the offset at which an item ends; an item
+/// starts where the previous one ends, or at the start of its data file if the previous one ends
+/// in another file. The first entry holds the number of items deleted from the tail instead.
+///
+/// # Fields
+///
+/// - `directory`: The directory holding the table files.
+/// - `name`: The name of the table.
+/// - `compressed`: Whether items are snappy-compressed.
+/// - `index`: The index entries.
+/// - `first_item`: The number of the first item still stored.
+/// - `file`: The data file opened by the last read.
+#[derive(Debug)]
+pub struct FreezerTable {
+    directory: PathBuf,
+    name: String,
+    compressed: bool,
+    index: Vec<IndexEntry>,
+    first_item: u64,
+    file: Option<(u32, File)>,
+}
+
+impl FreezerTable {
+    /// Opens a table, detecting whether it is compressed from its index file.
+    ///
+    /// # Arguments
+    ///
+    /// - `directory`: The freezer directory, e.g. `<datadir>/geth/chaindata/ancient/chain`.
+    /// - `name`: The name of the table, e.g. `HEADERS_TABLE`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, FreezerError>`, failing if the index file is missing or malformed.
+    pub fn open(directory: impl AsRef<Path>, name: &str) -> Result<Self, FreezerError> {
+        let directory = directory.as_ref().to_path_buf();
+        let (compressed, index_path) = [(true, "cidx"), (false, "ridx")]
+            .into_iter()
+            .map(|(compressed, extension)| {
+                (compressed, directory.join(format!("{name}.{extension}")))
+            })
+            .find(|(_, path)| path.exists())
+            .ok_or_else(|| FreezerError::MissingTable(name.to_string()))?;
+
+        let content = std::fs::read(&index_path)?;
+        let invalid_index = || FreezerError::InvalidIndex(index_path.display().to_string());
+        if content.is_empty() || !content.len().is_multiple_of(INDEX_ENTRY_LENGTH) {
+            return Err(invalid_index());
+        }
+        let index: Vec<IndexEntry> = content
+            .chunks(INDEX_ENTRY_LENGTH)
+            .map(|entry| IndexEntry {
+                file_number: u16::from_be_bytes([entry[0], entry[1]]) as u32,
+                offset: u32::from_be_bytes([entry[2], entry[3], entry[4], entry[5]]),
+            })
+            .collect();
+        if index
+            .windows(2)
+            .any(|window| window[1].file_number < window[0].file_number)
+        {
+            return Err(invalid_index());
+        }
+
+        Ok(FreezerTable {
+            directory,
+            name: name.to_string(),
+            compressed,
+            first_item: index[0].offset as u64,
+            index,
+            file: None,
+        })
+    }
+
+    /// Returns the numbers of the items stored in the table.
+    pub fn items(&self) -> Range<u64> {
+        self.first_item..self.first_item + (self.index.len() - 1) as u64
+    }
+
+    /// Reads an item, decompressing it if the table is compressed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<u8>, FreezerError>` containing the item, or an error if it is not stored or
+    /// cannot be read.
+    pub fn read(&mut self, number: u64) -> Result<Vec<u8>, FreezerError> {
+        if !self.items().contains(&number) {
+            return Err(FreezerError::ItemNotFound { number });
+        }
+        let position = (number - self.first_item) as usize;
+        let (start, end) = (self.index[position], self.index[position + 1]);
+        // The first entry holds no offset, the first item starting at the start of its file.
+        let start_offset = if position > 0 && start.file_number == end.file_number {
+            start.offset
+        } else {
+            0
+        };
+        if start_offset > end.offset {
+            return Err(FreezerError::InvalidIndex(self.name.clone()));
+        }
+
+        let file = self.data_file(end.file_number)?;
+        let mut data = vec![0; (end.offset - start_offset) as usize];
+        file.seek(SeekFrom::Start(start_offset as u64))?;
+        file.read_exact(&mut data).map_err(|error| {
+            if error.kind() == ErrorKind::UnexpectedEof {
+                FreezerError::TruncatedItem { number }
+            } else {
+                error.into()
+            }
+        })?;
+
+        if self.compressed {
+            Ok(snap::raw::Decoder::new().decompress_vec(&data)?)
+        } else {
+            Ok(data)
+        }
+    }
+
+    /// Returns the data file with the given number, keeping the last opened one.
+    fn data_file(&mut self, file_number: u32) -> Result<&mut File, FreezerError> {
+        if self
+            .file
+            .as_ref()
+            .is_none_or(|(number, _)| *number != file_number)
+        {
+            let extension = if self.compressed { "cdat" } else { "rdat" };
+            let path = self
+                .directory
+                .join(format!("{}.{file_number:04}.{extension}", self.name));
+            self.file = Some((file_number, File::open(path)?));
+        }
+        match &mut self.file {
+            Some((_, file)) => Ok(file),
+            None => unreachable!("the data file was just opened"),
+        }
+    }
+}
+
+/// A header read from the freezer.
+///
+/// # Fields
+///
+/// - `number`: The block number.
+/// - `hash`: The block hash stored in the hashes table.
+/// - `rlp`: The RLP encoded header stored in the headers table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FreezerHeader {
+    pub number: u64,
+    pub hash: H256,
+    pub rlp: Vec<u8>,
+}
+
+impl FreezerHeader {
+    /// Decodes the header with the decoder of its era, using the stored hash as block hash.
+    pub fn decode(&self) -> Result<VerifiableBlockHeader, FreezerError> {
+        let mut block_header =
+            decode_block_header(self.number, &self.rlp).ok_or(FreezerError::UndecodableHeader {
+                number: self.number,
+            })?;
+        block_header.block_hash = format!("{:?}", self.hash);
+        Ok(block_header)
+    }
+}
+
+/// The headers and hashes tables of a geth freezer.
+#[derive(Debug)]
+pub struct Freezer {
+    headers: FreezerTable,
+    hashes: FreezerTable,
+}
+
+impl Freezer {
+    /// Opens the headers and hashes tables of a freezer directory.
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, FreezerError> {
+        Ok(Freezer {
+            headers: FreezerTable::open(&directory, HEADERS_TABLE)?,
+            hashes: FreezerTable::open(&directory, HASHES_TABLE)?,
+        })
+    }
+
+    /// Returns the numbers of the blocks whose header and hash are both stored.
+    pub fn blocks(&self) -> Range<u64> {
+        let (headers, hashes) = (self.headers.items(), self.hashes.items());
+        headers.start.max(hashes.start)..headers.end.min(hashes.end)
+    }
+
+    /// Reads the header and hash of a block.
+    pub fn header(&mut self, number: u64) -> Result<FreezerHeader, FreezerError> {
+        let hash = self.hashes.read(number)?;
+        if hash.len() != 32 {
+            return Err(FreezerError::InvalidHash { number });
+        }
+        Ok(FreezerHeader {
+            number,
+            hash: H256::from_slice(&hash),
+            rlp: self.headers.read(number)?,
+        })
+    }
+
+    /// Reads the headers of a range of blocks, in ascending order.
+    pub fn headers(
+        &mut self,
+        range: RangeInclusive<u64>,
+    ) -> impl Iterator<Item = Result<FreezerHeader, FreezerError>> + '_ {
+        range.map(move |number| self.header(number))
+    }
+
+    /// Verifies a range of blocks.
+    ///
+    /// The stored RLP of each header is hashed as is and compared with its stored hash, so that
+    /// headers of every era verify without being decoded and encoded again. The parent hash read
+    /// from the RLP must also be the hash of the previous block.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<BlockResult>, FreezerError>` with one result per block, or an error if a
+    /// block cannot be read or has no parent hash.
+    pub fn verify_range(
+        &mut self,
+        range: RangeInclusive<u64>,
+    ) -> Result<Vec<BlockResult>, FreezerError> {
+        let mut previous = None;
+        self.headers(range)
+            .map(|header| {
+                let FreezerHeader { number, hash, rlp } = header?;
+                let parent_hash = rlp::Rlp::new(&rlp)
+                    .val_at::<H256>(0)
+                    .map_err(|_| FreezerError::UndecodableHeader { number })?;
+                let linked =
+                    previous.is_none_or(|previous| follows(previous, number, Some(parent_hash)));
+                previous = Some((number, Some(hash)));
+                Ok(BlockResult {
+                    number,
+                    block_hash: format!("{hash:?}"),
+                    hash_valid: keccak256(&rlp) == hash,
+                    linked,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::PRAGUE_START;
+    use crate::encode_block_header;
+    use crate::test_helpers::{create_test_chain, create_test_chain_prague};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Writes a freezer table, starting a new data file every `items_per_file` items.
+    fn write_table(
+        directory: &Path,
+        name: &str,
+        compressed: bool,
+        first_item: u32,
+        items: &[Vec<u8>],
+        items_per_file: usize,
+    ) {
+        let (index_extension, data_extension) = if compressed {
+            ("cidx", "cdat")
+        } else {
+            ("ridx", "rdat")
+        };
+        let mut index = [
+            0u16.to_be_bytes().to_vec(),
+            first_item.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        for (file_number, chunk) in items.chunks(items_per_file).enumerate() {
+            let mut data = vec![];
+            for item in chunk {
+                if compressed {
+                    data.extend(snap::raw::Encoder::new().compress_vec(item).unwrap());
+                } else {
+                    data.extend(item);
+                }
+                index.extend((file_number as u16).to_be_bytes());
+                index.extend((data.len() as u32).to_be_bytes());
+            }
+            fs::write(
+                directory.join(format!("{name}.{file_number:04}.{data_extension}")),
+                data,
+            )
+            .unwrap();
+        }
+        fs::write(directory.join(format!("{name}.{index_extension}")), index).unwrap();
+    }
+
+    /// Writes a freezer holding `block_headers` into a temporary directory.
+    fn write_freezer(block_headers: &[VerifiableBlockHeader]) -> TempDir {
+        let directory = TempDir::new().unwrap();
+        let first_item = block_headers[0].number as u32;
+        let headers: Vec<Vec<u8>> = block_headers
+            .iter()
+            .map(|h| encode_block_header(h.number as u64, h.clone()).unwrap())
+            .collect();
+        let hashes: Vec<Vec<u8>> = block_headers
+            .iter()
+            .map(|h| crate::payload::decode_hex(&h.block_hash).unwrap())
+            .collect();
+        write_table(
+            directory.path(),
+            HEADERS_TABLE,
+            true,
+            first_item,
+            &headers,
+            4,
+        );
+        write_table(
+            directory.path(),
+            HASHES_TABLE,
+            false,
+            first_item,
+            &hashes,
+            7,
+        );
+        directory
+    }
+
+    #[test]
+    fn test_read_freezer() {
+        let chain = create_test_chain(0, 10);
+        let directory = write_freezer(&chain[3..]);
+
+        let mut freezer = Freezer::open(directory.path()).unwrap();
+        assert_eq!(freezer.blocks(), 3..10);
+        for header in freezer.headers(3..=9) {
+            let header = header.unwrap();
+            let expected = &chain[header.number as usize];
+            let block_header = VerifiableBlockHeader {
+                ommers_hash: expected.sha3_uncles.clone(),
+                ..expected.clone()
+            };
+            assert_eq!(header.decode().unwrap(), block_header);
+        }
+        assert!(matches!(
+            freezer.header(2),
+            Err(FreezerError::ItemNotFound { number: 2 })
+        ));
+
+        let results = freezer.verify_range(3..=9).unwrap();
+        assert_eq!(results.len(), 7);
+        assert!(results.iter().all(BlockResult::is_valid));
+    }
+
+    #[test]
+    fn test_verify_tampered_freezer() {
+        let mut chain = create_test_chain(0, 8);
+        chain[5].gas_used += 1;
+        let directory = write_freezer(&chain);
+
+        let mut freezer = Freezer::open(directory.path()).unwrap();
+        let failures: Vec<(u64, bool, bool)> = freezer
+            .verify_range(0..=7)
+            .unwrap()
+            .into_iter()
+            .filter(|result| !result.is_valid())
+            .map(|result| (result.number, result.hash_valid, result.linked))
+            .collect();
+        assert_eq!(failures, vec![(5, false, true)]);
+
+        fs::write(directory.path().join("hashes.ridx"), [0; 5]).unwrap();
+        assert!(matches!(
+            Freezer::open(directory.path()),
+            Err(FreezerError::InvalidIndex(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_prague_freezer() {
+        let chain = create_test_chain_prague(3);
+        let directory = write_freezer(&chain);
+
+        let mut freezer = Freezer::open(directory.path()).unwrap();
+        let header = freezer.header(PRAGUE_START + 1).unwrap();
+        assert_eq!(
+            header.decode().unwrap().requests_hash,
+            chain[1].requests_hash
+        );
+        let results = freezer
+            .verify_range(PRAGUE_START..=PRAGUE_START + 2)
+            .unwrap();
+        assert!(results.iter().all(BlockResult::is_valid));
+    }
+}
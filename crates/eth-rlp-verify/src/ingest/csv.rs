@@ -0,0 +1,168 @@
+use super::{ColumnMapping, IngestError};
+use crate::chain::BlockResult;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+/// Reads headers from a CSV export with a header row.
+///
+/// Empty cells are read as missing values.
+pub struct CsvReader<R> {
+    records: ::csv::StringRecordsIntoIter<R>,
+    columns: HashMap<String, usize>,
+    mapping: ColumnMapping,
+    row: u64,
+}
+
+impl<R: Read> CsvReader<R> {
+    /// Creates a reader, reading the header row of the export.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, IngestError>`, failing if a column required by `mapping` is missing.
+    pub fn new(reader: R, mapping: ColumnMapping) -> Result<Self, IngestError> {
+        let mut reader = ::csv::Reader::from_reader(reader);
+        let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+        mapping.check_columns(&headers)?;
+        Ok(CsvReader {
+            records: reader.into_records(),
+            columns: headers
+                .into_iter()
+                .enumerate()
+                .map(|(index, column)| (column, index))
+                .collect(),
+            mapping,
+            row: 0,
+        })
+    }
+}
+
+impl<R: Read> Iterator for CsvReader<R> {
+    type Item = Result<VerifiableBlockHeader, IngestError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(error.into())),
+        };
+        let row = self.row;
+        self.row += 1;
+        Some(self.mapping.header_from_row(row, |column| {
+            let index = *self.columns.get(column)?;
+            record.get(index).map(str::to_string)
+        }))
+    }
+}
+
+/// Writes verification results as CSV, with a header row.
+pub fn write_results_csv<'a, W: Write>(
+    writer: W,
+    results: impl IntoIterator<Item = &'a BlockResult>,
+) -> Result<(), IngestError> {
+    let mut writer = ::csv::Writer::from_writer(writer);
+    for result in results {
+        writer.serialize(result)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainVerifier;
+    use crate::ingest::test_exports;
+
+    fn export(chain: &[VerifiableBlockHeader]) -> Vec<u8> {
+        let mut writer = ::csv::Writer::from_writer(vec![]);
+        let columns: Vec<&str> = test_exports::blocks_row(&chain[0])
+            .into_iter()
+            .map(|(column, _)| column)
+            .collect();
+        writer.write_record(&columns).unwrap();
+        for h in chain {
+            writer
+                .write_record(
+                    test_exports::blocks_row(h)
+                        .into_iter()
+                        .map(|(_, value)| value.unwrap_or_default()),
+                )
+                .unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let chain = test_exports::chain();
+        let export = export(&chain);
+        let headers: Vec<VerifiableBlockHeader> =
+            CsvReader::new(&export[..], ColumnMapping::blocks_table())
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            headers,
+            chain.iter().map(test_exports::expected).collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            CsvReader::new(&export[..], ColumnMapping::default()),
+            Err(IngestError::MissingColumn(column)) if column == "block_hash"
+        ));
+    }
+
+    #[test]
+    fn test_write_results_csv() {
+        let chain = test_exports::chain();
+        let mut verifier = ChainVerifier::new();
+        let results: Vec<BlockResult> = verifier.verify_iter(chain.clone()).collect();
+
+        let mut output = vec![];
+        write_results_csv(&mut output, &results[..2]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            format!(
+                "number,block_hash,hash_valid,linked\n0,{},true,true\n1,{},true,true\n",
+                chain[0].block_hash, chain[1].block_hash
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_bigquery_csv() {
+        // Mainnet block 1 in the CSV format of BigQuery's `crypto_ethereum.blocks`, which has no
+        // `mix_hash` column and writes timestamps as UTC dates.
+        let export = format!(
+            "timestamp,number,hash,parent_hash,nonce,sha3_uncles,logs_bloom,transactions_root,\
+            state_root,receipts_root,miner,difficulty,total_difficulty,size,extra_data,gas_limit,\
+            gas_used,transaction_count,base_fee_per_gas,withdrawals_root,blob_gas_used,\
+            excess_blob_gas\n\
+            2015-07-30 15:26:28 UTC,1,\
+            0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6,\
+            0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3,\
+            0x539bd4979fef1ec4,\
+            0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347,0x{},\
+            0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421,\
+            0xd67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3,\
+            0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421,\
+            0x05a56e2d52c817161883f50c441c3228cfe54d9f,17171480576,34351349760,537,\
+            0x476574682f76312e302e302f6c696e75782f676f312e342e32,5000,0,0,,,,\n",
+            "00".repeat(256)
+        );
+        let mut headers: Vec<VerifiableBlockHeader> =
+            CsvReader::new(export.as_bytes(), ColumnMapping::blocks_table())
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        let mut header = headers.remove(0);
+        assert_eq!(header.timestamp.as_deref(), Some("0x55ba4224"));
+        assert_eq!(header.mix_hash, None);
+
+        header.mix_hash =
+            Some("0x969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f59".to_string());
+        let block_hash = header.block_hash.clone();
+        assert!(crate::verify_block(1, header, &block_hash).unwrap());
+    }
+}
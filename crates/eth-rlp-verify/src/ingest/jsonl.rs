@@ -0,0 +1,144 @@
+use super::{ColumnMapping, IngestError};
+use crate::chain::BlockResult;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use serde_json::Value;
+use std::io::{BufRead, Lines, Write};
+
+/// Reads headers from a JSONL export, one JSON object per line.
+///
+/// String and number values are both accepted, and `null` values are read as missing values.
+/// Blank lines are skipped.
+pub struct JsonlReader<R> {
+    lines: Lines<R>,
+    mapping: ColumnMapping,
+    row: u64,
+}
+
+impl<R: BufRead> JsonlReader<R> {
+    /// Creates a reader over the lines of an export.
+    pub fn new(reader: R, mapping: ColumnMapping) -> Self {
+        JsonlReader {
+            lines: reader.lines(),
+            mapping,
+            row: 0,
+        }
+    }
+
+    fn read_row(&self, row: u64, line: &str) -> Result<VerifiableBlockHeader, IngestError> {
+        let Value::Object(object) = serde_json::from_str(line)? else {
+            return Err(IngestError::InvalidRow { row });
+        };
+        self.mapping
+            .header_from_row(row, |column| match object.get(column)? {
+                Value::String(value) => Some(value.clone()),
+                Value::Null => None,
+                value => Some(value.to_string()),
+            })
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlReader<R> {
+    type Item = Result<VerifiableBlockHeader, IngestError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => break line,
+                Err(error) => return Some(Err(error.into())),
+            }
+        };
+        let row = self.row;
+        self.row += 1;
+        Some(self.read_row(row, &line))
+    }
+}
+
+/// Writes verification results as JSONL, one JSON object per line.
+pub fn write_results_jsonl<'a, W: Write>(
+    mut writer: W,
+    results: impl IntoIterator<Item = &'a BlockResult>,
+) -> Result<(), IngestError> {
+    for result in results {
+        serde_json::to_writer(&mut writer, result)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainVerifier;
+    use crate::ingest::test_exports;
+    use serde_json::{Map, Number};
+
+    fn export(chain: &[VerifiableBlockHeader]) -> String {
+        chain
+            .iter()
+            .map(|h| {
+                let row: Map<String, Value> = test_exports::blocks_row(h)
+                    .into_iter()
+                    .map(|(column, value)| {
+                        let value = match value {
+                            // Exports write integer columns as JSON numbers.
+                            Some(value) if column == "number" || column.starts_with("gas") => {
+                                Value::Number(Number::from(value.parse::<u64>().unwrap()))
+                            }
+                            Some(value) => Value::String(value),
+                            None => Value::Null,
+                        };
+                        (column.to_string(), value)
+                    })
+                    .collect();
+                format!("{}\n", Value::Object(row))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_read_jsonl() {
+        let chain = test_exports::chain();
+        let export = format!("{}\n", export(&chain));
+        let headers: Vec<VerifiableBlockHeader> =
+            JsonlReader::new(export.as_bytes(), ColumnMapping::blocks_table())
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            headers,
+            chain.iter().map(test_exports::expected).collect::<Vec<_>>()
+        );
+
+        let mut reader = JsonlReader::new(
+            "[1, 2]\n{\"hash\": \"0x01\"}".as_bytes(),
+            ColumnMapping::blocks_table(),
+        );
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IngestError::InvalidRow { row: 0 }))
+        ));
+        assert!(matches!(
+            reader.next(),
+            Some(Err(IngestError::MissingValue { row: 1, column })) if column == "number"
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_write_results_jsonl() {
+        let chain = test_exports::chain();
+        let mut verifier = ChainVerifier::new();
+        let results: Vec<BlockResult> = verifier.verify_iter(chain.clone()).collect();
+
+        let mut output = vec![];
+        write_results_jsonl(&mut output, &results[..1]).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!(
+                "{{\"number\":0,\"block_hash\":\"{}\",\"hash_valid\":true,\"linked\":true}}\n",
+                chain[0].block_hash
+            )
+        );
+    }
+}
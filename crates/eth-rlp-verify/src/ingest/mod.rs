@@ -0,0 +1,467 @@
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "jsonl")]
+mod jsonl;
+#[cfg(feature = "parquet")]
+mod parquet;
+
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::U256;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[cfg(feature = "csv")]
+pub use self::csv::{write_results_csv, CsvReader};
+#[cfg(feature = "jsonl")]
+pub use self::jsonl::{write_results_jsonl, JsonlReader};
+#[cfg(feature = "parquet")]
+pub use self::parquet::{write_results_parquet, ParquetReader};
+
+#[derive(Error, Debug)]
+pub enum IngestError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[cfg(feature = "jsonl")]
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "csv")]
+    #[error("CSV error: {0}")]
+    CsvError(#[from] ::csv::Error),
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    ParquetError(#[from] ::parquet::errors::ParquetError),
+    #[error("Unknown header field {0}")]
+    UnknownField(String),
+    #[error("Missing column {0}")]
+    MissingColumn(String),
+    #[error("Row {row} is not an object")]
+    InvalidRow { row: u64 },
+    #[error("Row {row} has no value in column {column}")]
+    MissingValue { row: u64, column: String },
+    #[error("Row {row} has an invalid value in column {column}: {value}")]
+    InvalidValue {
+        row: u64,
+        column: String,
+        value: String,
+    },
+}
+
+/// How the values of a column are normalized into a `BlockHeader` field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldKind {
+    /// A hex string, prefixed with `0x` if the export left it out.
+    Data,
+    /// A quantity given in decimal or in hex, stored as a hex string.
+    Quantity,
+    /// A quantity stored as an `i64`.
+    Integer,
+    /// A Unix timestamp given as a quantity or as a UTC date and time, e.g.
+    /// `2015-07-30 15:26:28 UTC`, stored as a hex string.
+    Timestamp,
+}
+
+/// The `BlockHeader` fields, whether a value is required, and how their values are normalized.
+const HEADER_FIELDS: [(&str, bool, FieldKind); 24] = [
+    ("block_hash", true, FieldKind::Data),
+    ("number", true, FieldKind::Integer),
+    ("gas_limit", true, FieldKind::Integer),
+    ("gas_used", true, FieldKind::Integer),
+    ("nonce", true, FieldKind::Data),
+    ("transaction_root", false, FieldKind::Data),
+    ("receipts_root", false, FieldKind::Data),
+    ("state_root", false, FieldKind::Data),
+    ("base_fee_per_gas", false, FieldKind::Quantity),
+    ("parent_hash", false, FieldKind::Data),
+    ("ommers_hash", false, FieldKind::Data),
+    ("miner", false, FieldKind::Data),
+    ("logs_bloom", false, FieldKind::Data),
+    ("difficulty", false, FieldKind::Quantity),
+    ("totaldifficulty", false, FieldKind::Quantity),
+    ("sha3_uncles", false, FieldKind::Data),
+    ("timestamp", false, FieldKind::Timestamp),
+    ("extra_data", false, FieldKind::Data),
+    ("mix_hash", false, FieldKind::Data),
+    ("withdrawals_root", false, FieldKind::Data),
+    ("blob_gas_used", false, FieldKind::Quantity),
+    ("excess_blob_gas", false, FieldKind::Quantity),
+    ("parent_beacon_block_root", false, FieldKind::Data),
+    ("requests_hash", false, FieldKind::Data),
+];
+
+/// Maps the fields of `BlockHeader` to the columns of an export.
+///
+/// By default, every field is read from the column of the same name. Columns of optional fields
+/// may be absent from the export, in which case the field is left empty.
+///
+/// # Fields
+///
+/// - `columns`: The column of every `BlockHeader` field.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnMapping {
+    columns: BTreeMap<&'static str, String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        ColumnMapping {
+            columns: HEADER_FIELDS
+                .iter()
+                .map(|(field, _, _)| (*field, field.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl ColumnMapping {
+    /// Returns the mapping of the `blocks` tables of Dune and BigQuery style exports, which name
+    /// the block hash `hash` and spell out the trie roots and the total difficulty.
+    pub fn blocks_table() -> Self {
+        [
+            ("block_hash", "hash"),
+            ("transaction_root", "transactions_root"),
+            ("totaldifficulty", "total_difficulty"),
+            ("ommers_hash", "sha3_uncles"),
+        ]
+        .into_iter()
+        .fold(Self::default(), |mapping, (field, column)| {
+            mapping.set(field, column)
+        })
+    }
+
+    /// Reads a field from another column.
+    ///
+    /// # Arguments
+    ///
+    /// - `field`: The name of the `BlockHeader` field, e.g. `block_hash`.
+    /// - `column`: The name of the column holding it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, IngestError>`, failing if `field` is not a `BlockHeader` field.
+    pub fn with_column(self, field: &str, column: &str) -> Result<Self, IngestError> {
+        match HEADER_FIELDS.iter().find(|(name, _, _)| *name == field) {
+            Some((field, _, _)) => Ok(self.set(field, column)),
+            None => Err(IngestError::UnknownField(field.to_string())),
+        }
+    }
+
+    fn set(mut self, field: &'static str, column: &str) -> Self {
+        self.columns.insert(field, column.to_string());
+        self
+    }
+
+    /// Returns the column a field is read from.
+    pub fn column(&self, field: &str) -> Option<&str> {
+        self.columns.get(field).map(String::as_str)
+    }
+
+    /// Checks that the columns of the required fields are present in an export.
+    #[cfg(any(feature = "csv", feature = "parquet"))]
+    pub(crate) fn check_columns(&self, columns: &[String]) -> Result<(), IngestError> {
+        for (field, required, _) in HEADER_FIELDS {
+            let column = &self.columns[field];
+            if required && !columns.contains(column) {
+                return Err(IngestError::MissingColumn(column.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a header from a row of an export.
+    ///
+    /// # Arguments
+    ///
+    /// - `row`: The index of the row, used in errors.
+    /// - `value`: Returns the value of a column in the row, or `None` if it is absent or null.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<VerifiableBlockHeader, IngestError>`, failing if a required value is missing or
+    /// a value cannot be normalized.
+    pub(crate) fn header_from_row(
+        &self,
+        row: u64,
+        value: impl Fn(&str) -> Option<String>,
+    ) -> Result<VerifiableBlockHeader, IngestError> {
+        let mut values = BTreeMap::new();
+        for (field, required, kind) in HEADER_FIELDS {
+            let column = &self.columns[field];
+            let normalized = match value(column).filter(|value| !value.is_empty()) {
+                Some(value) => Some(normalize(kind, &value).ok_or(IngestError::InvalidValue {
+                    row,
+                    column: column.clone(),
+                    value,
+                })?),
+                None if required => {
+                    return Err(IngestError::MissingValue {
+                        row,
+                        column: column.clone(),
+                    })
+                }
+                None => None,
+            };
+            values.insert(field, normalized);
+        }
+
+        let mut take = |field: &str| values.remove(field).flatten();
+        let integer = |value: Option<String>| -> i64 {
+            value
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default()
+        };
+        Ok(VerifiableBlockHeader {
+            number: integer(take("number")),
+            gas_limit: integer(take("gas_limit")),
+            gas_used: integer(take("gas_used")),
+            block_hash: take("block_hash").unwrap_or_default(),
+            nonce: take("nonce").unwrap_or_default(),
+            transaction_root: take("transaction_root"),
+            receipts_root: take("receipts_root"),
+            state_root: take("state_root"),
+            base_fee_per_gas: take("base_fee_per_gas"),
+            parent_hash: take("parent_hash"),
+            ommers_hash: take("ommers_hash"),
+            miner: take("miner"),
+            logs_bloom: take("logs_bloom"),
+            difficulty: take("difficulty"),
+            totaldifficulty: take("totaldifficulty"),
+            sha3_uncles: take("sha3_uncles"),
+            timestamp: take("timestamp"),
+            extra_data: take("extra_data"),
+            mix_hash: take("mix_hash"),
+            withdrawals_root: take("withdrawals_root"),
+            blob_gas_used: take("blob_gas_used"),
+            excess_blob_gas: take("excess_blob_gas"),
+            parent_beacon_block_root: take("parent_beacon_block_root"),
+            requests_hash: take("requests_hash"),
+        })
+    }
+}
+
+/// Parses a quantity given in decimal or in `0x` prefixed hex.
+fn parse_quantity(value: &str) -> Option<U256> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+}
+
+/// Parses a fixed number of decimal digits.
+fn parse_digits(value: &str, digits: usize) -> Option<i64> {
+    (value.len() == digits && value.chars().all(|c| c.is_ascii_digit()))
+        .then(|| value.parse().ok())
+        .flatten()
+}
+
+/// Splits the UTC offset from a date and time, given as ` UTC`, `Z` or `+hh:mm`.
+///
+/// # Returns
+///
+/// The date and time, and the offset in seconds.
+fn split_utc_offset(value: &str) -> Option<(&str, i64)> {
+    if let Some(date_time) = value
+        .strip_suffix(" UTC")
+        .or_else(|| value.strip_suffix('Z'))
+    {
+        return Some((date_time, 0));
+    }
+    let at = value.len().checked_sub(6)?;
+    let (date_time, suffix) = (value.get(..at)?, value.get(at..)?);
+    let (hours, minutes) = suffix.get(1..)?.split_once(':')?;
+    let offset = parse_digits(hours, 2)? * 3_600 + parse_digits(minutes, 2)? * 60;
+    match suffix.as_bytes().first() {
+        Some(b'+') => Some((date_time, offset)),
+        Some(b'-') => Some((date_time, -offset)),
+        _ => None,
+    }
+}
+
+/// Parses a date and time as written by BigQuery exports, e.g. `2015-07-30 15:26:28 UTC`, or in
+/// RFC 3339, e.g. `2015-07-30T15:26:28Z`, into seconds since the Unix epoch.
+///
+/// Fractional seconds must be zero, since block timestamps are whole seconds.
+fn parse_date_time(value: &str) -> Option<u64> {
+    let (date_time, offset) = split_utc_offset(value)?;
+    let (date, time) = date_time
+        .split_once(' ')
+        .or_else(|| date_time.split_once('T'))?;
+    let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+    if !fraction.chars().all(|c| c == '0') {
+        return None;
+    }
+    let fields = |value: &str, separator: char, digits: [usize; 3]| -> Option<[i64; 3]> {
+        let parts: Vec<&str> = value.split(separator).collect();
+        match parts[..] {
+            [a, b, c] => Some([
+                parse_digits(a, digits[0])?,
+                parse_digits(b, digits[1])?,
+                parse_digits(c, digits[2])?,
+            ]),
+            _ => None,
+        }
+    };
+    let [year, month, day] = fields(date, '-', [4, 2, 2])?;
+    let [hour, minute, second] = fields(time, ':', [2, 2, 2])?;
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    // Days since the epoch of a proleptic Gregorian date, with years starting in March so that
+    // the leap day is the last day of the year.
+    let year = if month <= 2 { year - 1 } else { year };
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = year.div_euclid(400) * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3_600 + minute * 60 + second - offset).ok()
+}
+
+/// Normalizes a value into the format `BlockHeader` fields are stored in.
+fn normalize(kind: FieldKind, value: &str) -> Option<String> {
+    match kind {
+        FieldKind::Data => {
+            let hex = value.strip_prefix("0x").unwrap_or(value);
+            hex.chars()
+                .all(|c| c.is_ascii_hexdigit())
+                .then(|| format!("0x{hex}"))
+        }
+        FieldKind::Quantity => parse_quantity(value).map(|quantity| format!("{quantity:#x}")),
+        FieldKind::Timestamp => parse_quantity(value)
+            .or_else(|| parse_date_time(value).map(U256::from))
+            .map(|timestamp| format!("{timestamp:#x}")),
+        FieldKind::Integer => parse_quantity(value)
+            .and_then(|quantity| i64::try_from(quantity).ok())
+            .map(|quantity| quantity.to_string()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_exports {
+    use crate::test_helpers::create_test_chain;
+    use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+
+    /// A chain of headers, as exported by a `blocks` table with decimal quantities.
+    pub(crate) fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(0, 5)
+    }
+
+    /// Returns the `blocks` table columns and the values of a row, with decimal quantities and
+    /// unprefixed nonces as found in exports.
+    pub(crate) fn blocks_row(h: &VerifiableBlockHeader) -> Vec<(&'static str, Option<String>)> {
+        let decimal = |value: &Option<String>| {
+            value.as_ref().map(|value| {
+                u128::from_str_radix(value.trim_start_matches("0x"), 16)
+                    .unwrap()
+                    .to_string()
+            })
+        };
+        vec![
+            ("hash", Some(h.block_hash.clone())),
+            ("number", Some(h.number.to_string())),
+            ("gas_limit", Some(h.gas_limit.to_string())),
+            ("gas_used", Some(h.gas_used.to_string())),
+            ("nonce", Some(h.nonce.trim_start_matches("0x").to_string())),
+            ("transactions_root", h.transaction_root.clone()),
+            ("receipts_root", h.receipts_root.clone()),
+            ("state_root", h.state_root.clone()),
+            ("parent_hash", h.parent_hash.clone()),
+            ("miner", h.miner.clone()),
+            ("logs_bloom", h.logs_bloom.clone()),
+            ("difficulty", decimal(&h.difficulty)),
+            ("sha3_uncles", h.sha3_uncles.clone()),
+            ("timestamp", decimal(&h.timestamp)),
+            ("extra_data", h.extra_data.clone()),
+            ("mix_hash", h.mix_hash.clone()),
+            ("base_fee_per_gas", None),
+        ]
+    }
+
+    /// Returns the header expected from reading a `blocks` table row.
+    pub(crate) fn expected(h: &VerifiableBlockHeader) -> VerifiableBlockHeader {
+        VerifiableBlockHeader {
+            ommers_hash: h.sha3_uncles.clone(),
+            ..h.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainVerifier;
+
+    #[test]
+    fn test_header_from_row() {
+        let mapping = ColumnMapping::blocks_table();
+        let chain = test_exports::chain();
+        let mut verifier = ChainVerifier::new();
+        for h in &chain {
+            let row: BTreeMap<_, _> = test_exports::blocks_row(h).into_iter().collect();
+            let header = mapping
+                .header_from_row(0, |column| row.get(column).cloned().flatten())
+                .unwrap();
+            assert_eq!(header, test_exports::expected(h));
+            assert!(verifier.push(header).is_valid());
+        }
+
+        assert!(matches!(
+            mapping.header_from_row(3, |_| Some("not hex".to_string())),
+            Err(IngestError::InvalidValue { row: 3, .. })
+        ));
+        assert!(matches!(
+            mapping.header_from_row(4, |_| None),
+            Err(IngestError::MissingValue { row: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        for (value, timestamp) in [
+            ("1438269988", "0x55ba4224"),
+            ("0x55ba4224", "0x55ba4224"),
+            ("2015-07-30 15:26:28 UTC", "0x55ba4224"),
+            ("2015-07-30T15:26:28Z", "0x55ba4224"),
+            ("2015-07-30T17:26:28.000+02:00", "0x55ba4224"),
+            ("1970-01-01 00:00:00 UTC", "0x0"),
+            ("2024-02-29 12:00:00 UTC", "0x65e071c0"),
+        ] {
+            assert_eq!(
+                normalize(FieldKind::Timestamp, value).as_deref(),
+                Some(timestamp),
+                "{value}"
+            );
+        }
+        for value in [
+            "2015-07-30 15:26:28",
+            "2015-07-30 15:26:28.5 UTC",
+            "2015-13-30 15:26:28 UTC",
+            "1969-12-31 23:59:59 UTC",
+        ] {
+            assert_eq!(normalize(FieldKind::Timestamp, value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn test_column_mapping() {
+        let mapping = ColumnMapping::default()
+            .with_column("block_hash", "hash")
+            .unwrap();
+        assert_eq!(mapping.column("block_hash"), Some("hash"));
+        assert_eq!(mapping.column("number"), Some("number"));
+        assert!(matches!(
+            mapping.clone().with_column("size", "size"),
+            Err(IngestError::UnknownField(_))
+        ));
+        #[cfg(any(feature = "csv", feature = "parquet"))]
+        assert!(matches!(
+            mapping.check_columns(&["hash".to_string(), "number".to_string()]),
+            Err(IngestError::MissingColumn(column)) if column == "gas_limit"
+        ));
+    }
+}
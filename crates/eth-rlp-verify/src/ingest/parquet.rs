@@ -0,0 +1,275 @@
+use super::{ColumnMapping, IngestError};
+use crate::chain::BlockResult;
+use ::parquet::basic::Compression;
+use ::parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int64Type};
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::record::reader::RowIter;
+use ::parquet::record::Field;
+use ::parquet::schema::parser::parse_message_type;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::U256;
+use std::fs::File;
+use std::io::Write;
+use std::sync::Arc;
+
+/// The schema of the verification results written by `write_results_parquet`.
+const RESULTS_SCHEMA: &str = "message block_verification_results {
+    REQUIRED INT64 number;
+    REQUIRED BYTE_ARRAY block_hash (UTF8);
+    REQUIRED BOOLEAN hash_valid;
+    REQUIRED BOOLEAN linked;
+}";
+
+/// Converts a Parquet value into the text the column mapping normalizes.
+///
+/// Binary values, e.g. hashes stored as `varbinary`, are hex encoded, timestamps are converted
+/// to seconds, and integral decimals, e.g. a `NUMERIC` difficulty, are written in decimal.
+fn field_value(field: &Field) -> Option<String> {
+    match field {
+        Field::Null => None,
+        Field::Str(value) => Some(value.clone()),
+        Field::Bytes(value) => Some(format!("0x{}", hex::encode(value.data()))),
+        Field::TimestampMillis(millis) => Some((millis / 1_000).to_string()),
+        Field::TimestampMicros(micros) => Some((micros / 1_000_000).to_string()),
+        Field::Decimal(decimal) if decimal.scale() == 0 && decimal.data().len() <= 32 => {
+            Some(U256::from_big_endian(decimal.data()).to_string())
+        }
+        field => Some(field.to_string()),
+    }
+}
+
+/// Reads headers from a Parquet export, row by row.
+///
+/// Columns may hold strings, integers, binary values, integral decimals or timestamps, and null
+/// values are read as missing values.
+pub struct ParquetReader {
+    rows: RowIter<'static>,
+    mapping: ColumnMapping,
+    row: u64,
+}
+
+impl ParquetReader {
+    /// Opens a Parquet file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, IngestError>`, failing if the file is not a Parquet file or if a column
+    /// required by `mapping` is missing.
+    pub fn open(file: File, mapping: ColumnMapping) -> Result<Self, IngestError> {
+        let reader = SerializedFileReader::new(file)?;
+        let columns: Vec<String> = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect();
+        mapping.check_columns(&columns)?;
+        Ok(ParquetReader {
+            rows: reader.into_iter(),
+            mapping,
+            row: 0,
+        })
+    }
+}
+
+impl Iterator for ParquetReader {
+    type Item = Result<VerifiableBlockHeader, IngestError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.rows.next()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(error.into())),
+        };
+        let row = self.row;
+        self.row += 1;
+        Some(self.mapping.header_from_row(row, |column| {
+            record
+                .get_column_iter()
+                .find(|(name, _)| *name == column)
+                .and_then(|(_, field)| field_value(field))
+        }))
+    }
+}
+
+/// Writes verification results as a snappy-compressed Parquet file, in a single row group.
+pub fn write_results_parquet<'a, W: Write + Send>(
+    writer: W,
+    results: impl IntoIterator<Item = &'a BlockResult>,
+) -> Result<(), IngestError> {
+    let results: Vec<&BlockResult> = results.into_iter().collect();
+    let numbers: Vec<i64> = results.iter().map(|result| result.number as i64).collect();
+    let block_hashes: Vec<ByteArray> = results
+        .iter()
+        .map(|result| ByteArray::from(result.block_hash.as_str()))
+        .collect();
+    let hash_valid: Vec<bool> = results.iter().map(|result| result.hash_valid).collect();
+    let linked: Vec<bool> = results.iter().map(|result| result.linked).collect();
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = SerializedFileWriter::new(
+        writer,
+        Arc::new(parse_message_type(RESULTS_SCHEMA)?),
+        Arc::new(properties),
+    )?;
+    let mut row_group = writer.next_row_group()?;
+    let missing_column = || ::parquet::errors::ParquetError::General("missing column".into());
+
+    let mut column = row_group.next_column()?.ok_or_else(missing_column)?;
+    column
+        .typed::<Int64Type>()
+        .write_batch(&numbers, None, None)?;
+    column.close()?;
+    let mut column = row_group.next_column()?.ok_or_else(missing_column)?;
+    column
+        .typed::<ByteArrayType>()
+        .write_batch(&block_hashes, None, None)?;
+    column.close()?;
+    for values in [&hash_valid, &linked] {
+        let mut column = row_group.next_column()?.ok_or_else(missing_column)?;
+        column.typed::<BoolType>().write_batch(values, None, None)?;
+        column.close()?;
+    }
+
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainVerifier;
+    use crate::ingest::test_exports;
+    use ::parquet::record::RowAccessor;
+    use std::path::Path;
+    use tempfile::NamedTempFile;
+
+    /// Writes a `blocks` table export, with integer columns as `INT64`, the block hash as binary
+    /// and the timestamp as a `TIMESTAMP_MILLIS`.
+    fn export(path: &Path, chain: &[VerifiableBlockHeader]) {
+        let rows: Vec<_> = chain.iter().map(test_exports::blocks_row).collect();
+        let schema: String = rows[0]
+            .iter()
+            .map(|(column, _)| match *column {
+                "number" | "gas_limit" | "gas_used" => format!("REQUIRED INT64 {column};"),
+                "timestamp" => "OPTIONAL INT64 timestamp (TIMESTAMP_MILLIS);".to_string(),
+                "hash" => "REQUIRED BYTE_ARRAY hash;".to_string(),
+                column => format!("OPTIONAL BYTE_ARRAY {column} (UTF8);"),
+            })
+            .collect();
+        let schema = parse_message_type(&format!("message blocks {{ {schema} }}")).unwrap();
+        let mut writer = SerializedFileWriter::new(
+            File::create(path).unwrap(),
+            Arc::new(schema),
+            Arc::new(WriterProperties::builder().build()),
+        )
+        .unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        for (index, (column, _)) in rows[0].iter().enumerate() {
+            let values: Vec<Option<String>> = rows.iter().map(|row| row[index].1.clone()).collect();
+            let definition_levels: Vec<i16> = values.iter().map(|v| v.is_some() as i16).collect();
+            let present = values.iter().flatten();
+            let mut writer = row_group.next_column().unwrap().unwrap();
+            match *column {
+                "number" | "gas_limit" | "gas_used" => {
+                    let values: Vec<i64> = present.map(|v| v.parse().unwrap()).collect();
+                    writer
+                        .typed::<Int64Type>()
+                        .write_batch(&values, None, None)
+                        .unwrap();
+                }
+                "timestamp" => {
+                    let values: Vec<i64> =
+                        present.map(|v| v.parse::<i64>().unwrap() * 1_000).collect();
+                    writer
+                        .typed::<Int64Type>()
+                        .write_batch(&values, Some(&definition_levels), None)
+                        .unwrap();
+                }
+                "hash" => {
+                    let values: Vec<ByteArray> = present
+                        .map(|v| ByteArray::from(hex::decode(&v[2..]).unwrap()))
+                        .collect();
+                    writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, None, None)
+                        .unwrap();
+                }
+                _ => {
+                    let values: Vec<ByteArray> =
+                        present.map(|v| ByteArray::from(v.as_str())).collect();
+                    writer
+                        .typed::<ByteArrayType>()
+                        .write_batch(&values, Some(&definition_levels), None)
+                        .unwrap();
+                }
+            }
+            writer.close().unwrap();
+        }
+        row_group.close().unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_read_parquet() {
+        let chain = test_exports::chain();
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+        export(path, &chain);
+
+        let headers: Vec<VerifiableBlockHeader> =
+            ParquetReader::open(File::open(path).unwrap(), ColumnMapping::blocks_table())
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(
+            headers,
+            chain.iter().map(test_exports::expected).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_write_results_parquet() {
+        let mut chain = test_exports::chain();
+        chain[2].gas_used += 1;
+        let mut verifier = ChainVerifier::new();
+        let results: Vec<BlockResult> = verifier.verify_iter(chain.clone()).collect();
+
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path();
+        write_results_parquet(File::create(path).unwrap(), &results).unwrap();
+        let rows: Vec<(i64, String, bool, bool)> =
+            SerializedFileReader::new(File::open(path).unwrap())
+                .unwrap()
+                .into_iter()
+                .map(|row| {
+                    let row = row.unwrap();
+                    (
+                        row.get_long(0).unwrap(),
+                        row.get_string(1).unwrap().clone(),
+                        row.get_bool(2).unwrap(),
+                        row.get_bool(3).unwrap(),
+                    )
+                })
+                .collect();
+        let expected: Vec<(i64, String, bool, bool)> = results
+            .iter()
+            .map(|r| {
+                (
+                    r.number as i64,
+                    r.block_hash.clone(),
+                    r.hash_valid,
+                    r.linked,
+                )
+            })
+            .collect();
+        assert_eq!(rows, expected);
+        assert!(!rows[2].2);
+    }
+}
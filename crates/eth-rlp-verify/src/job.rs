@@ -0,0 +1,243 @@
+use crate::chain::{follows, parse_hash, verify_header_hash, ChainError};
+use crate::checkpoint::Checkpoint;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use primitive_types::H256;
+use std::fs;
+use std::io::ErrorKind;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JobError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid progress file {0}")]
+    InvalidProgressFile(String),
+    #[error("Batch is empty")]
+    EmptyBatch,
+    #[error("Chain error: {0}")]
+    ChainError(#[from] ChainError),
+    #[error("Source error: {0}")]
+    SourceError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Parses the content of a progress file, `<number> <hash>` on a single line.
+fn parse_progress(content: &str) -> Option<Checkpoint> {
+    let (number, hash) = content.trim().split_once(' ')?;
+    Some(Checkpoint {
+        number: number.parse().ok()?,
+        hash: H256::from_str(hash).ok()?,
+    })
+}
+
+/// A chain verification job persisting its progress to a local file, so that an interrupted
+/// backfill resumes from the last verified block instead of from the start.
+///
+/// After every verified batch, the number and hash of its last block are written to the progress
+/// file. The next batch, possibly in another process, must link to that block: its first block
+/// must follow it and commit to its hash as parent hash.
+///
+/// # Fields
+///
+/// - `path`: The path of the progress file.
+/// - `progress`: The last verified block, if any.
+#[derive(Clone, Debug)]
+pub struct ResumableJob {
+    path: PathBuf,
+    progress: Option<Checkpoint>,
+}
+
+impl ResumableJob {
+    /// Opens a job, resuming from the progress file at `path` if it exists.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Self, JobError>`, failing if the progress file exists but cannot be read.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, JobError> {
+        let path = path.as_ref().to_path_buf();
+        let progress = match fs::read_to_string(&path) {
+            Ok(content) => Some(
+                parse_progress(&content)
+                    .ok_or_else(|| JobError::InvalidProgressFile(path.display().to_string()))?,
+            ),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        };
+        Ok(ResumableJob { path, progress })
+    }
+
+    /// Returns the last verified block, if any.
+    pub fn progress(&self) -> Option<Checkpoint> {
+        self.progress
+    }
+
+    /// Returns the number of the next block to verify, or `start` if nothing was verified yet.
+    pub fn next_block(&self, start: u64) -> u64 {
+        self.progress.map_or(start, |progress| progress.number + 1)
+    }
+
+    /// Writes the progress file, replacing it atomically so that an interruption cannot leave it
+    /// truncated.
+    fn save(&self, progress: Checkpoint) -> Result<(), JobError> {
+        let temporary = self.path.with_extension("tmp");
+        fs::write(
+            &temporary,
+            format!("{} {:?}\n", progress.number, progress.hash),
+        )?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    /// Verifies the next batch of headers and records its last block as the job's progress.
+    ///
+    /// # Arguments
+    ///
+    /// - `block_headers`: The headers following the last verified block, in ascending order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Checkpoint, JobError>` containing the new progress, or an error describing the
+    /// first problem found. The progress is left unchanged on error.
+    pub fn verify_batch(
+        &mut self,
+        block_headers: &[VerifiableBlockHeader],
+    ) -> Result<Checkpoint, JobError> {
+        let mut previous = self
+            .progress
+            .map(|progress| (progress.number, Some(progress.hash)));
+        for block_header in block_headers {
+            let number = block_header.number as u64;
+            let parent_hash = parse_hash(&block_header.parent_hash.clone().unwrap_or_default())?;
+            if let Some(previous) = previous {
+                if number != previous.0 + 1 {
+                    return Err(ChainError::NonConsecutiveBlock { number }.into());
+                }
+                if !follows(previous, number, Some(parent_hash)) {
+                    return Err(ChainError::BrokenLink { number }.into());
+                }
+            }
+            previous = Some((number, Some(verify_header_hash(block_header)?)));
+        }
+
+        let progress = match block_headers.last() {
+            Some(last) => Checkpoint {
+                number: last.number as u64,
+                hash: parse_hash(&last.block_hash)?,
+            },
+            None => return Err(JobError::EmptyBatch),
+        };
+        self.save(progress)?;
+        self.progress = Some(progress);
+        Ok(progress)
+    }
+
+    /// Verifies every block up to `end`, fetching batches from a source.
+    ///
+    /// The job resumes after its last verified block, or at `start` if nothing was verified yet.
+    ///
+    /// # Arguments
+    ///
+    /// - `start`: The first block to verify when the job has no progress.
+    /// - `end`: The last block to verify.
+    /// - `batch_size`: The number of blocks fetched and verified at once.
+    /// - `fetch`: Returns the headers of a range of blocks, e.g. from a database or a node.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Option<Checkpoint>, JobError>` containing the final progress.
+    pub fn run<F, E>(
+        &mut self,
+        start: u64,
+        end: u64,
+        batch_size: u64,
+        mut fetch: F,
+    ) -> Result<Option<Checkpoint>, JobError>
+    where
+        F: FnMut(RangeInclusive<u64>) -> Result<Vec<VerifiableBlockHeader>, E>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let batch_size = batch_size.max(1);
+        let mut next = self.next_block(start);
+        while next <= end {
+            let batch_end = end.min(next.saturating_add(batch_size - 1));
+            let block_headers =
+                fetch(next..=batch_end).map_err(|error| JobError::SourceError(error.into()))?;
+            let progress = self.verify_batch(&block_headers)?;
+            next = progress.number + 1;
+        }
+        Ok(self.progress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{create_test_chain, create_test_chain_genesis};
+    use tempfile::TempDir;
+
+    fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(0, 20)
+    }
+
+    /// Returns a progress file path in a fresh directory, removed when the directory is dropped.
+    fn progress_path() -> (TempDir, PathBuf) {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("job.progress");
+        (directory, path)
+    }
+
+    #[test]
+    fn test_resume_after_interruption() {
+        let chain = chain();
+        let (_directory, path) = progress_path();
+        let fetch = |range: RangeInclusive<u64>| -> Result<_, std::io::Error> {
+            Ok(chain[*range.start() as usize..=*range.end() as usize].to_vec())
+        };
+
+        let mut job = ResumableJob::open(&path).unwrap();
+        let progress = job.run(0, 11, 4, fetch).unwrap().unwrap();
+        assert_eq!(progress.number, 11);
+
+        // A new process picks up where the previous one stopped.
+        let mut job = ResumableJob::open(&path).unwrap();
+        assert_eq!(job.progress(), Some(progress));
+        assert_eq!(job.next_block(0), 12);
+        let mut fetched = vec![];
+        job.run(0, 19, 4, |range| {
+            fetched.push(range.clone());
+            fetch(range)
+        })
+        .unwrap();
+        assert_eq!(fetched, vec![12..=15, 16..=19]);
+        assert_eq!(job.progress().unwrap().number, 19);
+    }
+
+    #[test]
+    fn test_batch_must_link_to_progress() {
+        let chain = chain();
+        let forked = create_test_chain_genesis(&chain[4].block_hash, 5, 3, 1);
+        let (_directory, path) = progress_path();
+
+        let mut job = ResumableJob::open(&path).unwrap();
+        job.verify_batch(&chain[..6]).unwrap();
+        assert!(matches!(
+            job.verify_batch(&forked[1..]),
+            Err(JobError::ChainError(ChainError::BrokenLink { number: 6 }))
+        ));
+        assert!(matches!(
+            job.verify_batch(&chain[7..]),
+            Err(JobError::ChainError(ChainError::NonConsecutiveBlock {
+                number: 7
+            }))
+        ));
+        assert_eq!(job.progress().unwrap().number, 5);
+
+        fs::write(&path, "not a checkpoint").unwrap();
+        assert!(matches!(
+            ResumableJob::open(&path),
+            Err(JobError::InvalidProgressFile(_))
+        ));
+    }
+}
@@ -1,9 +1,34 @@
 #![deny(unused_crate_dependencies)]
 
+pub mod accumulator;
+pub mod archive;
+pub mod audit;
+pub mod beacon;
+pub mod chain;
+pub mod checkpoint;
 pub mod constants;
+#[cfg(feature = "sqlx")]
+pub mod db;
+pub mod era;
 pub mod eras;
+pub mod freezer;
+#[cfg(any(feature = "csv", feature = "jsonl", feature = "parquet"))]
+pub mod ingest;
+pub mod job;
+pub mod mmr;
+pub mod payload;
+pub mod proofs;
+pub mod rpc;
+pub mod ssz;
+#[cfg(feature = "async")]
+pub mod stream;
 pub mod test_helpers;
 pub mod traits;
+
+// Only the database tests run on a Tokio runtime.
+#[cfg(all(test, not(feature = "sqlx")))]
+use tokio as _;
+
 use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError};
 
 pub fn are_blocks_and_chain_valid(block_headers: &[VerifiableBlockHeader]) -> bool {
@@ -93,8 +118,59 @@ pub fn decode_block_header(block_number: u64, encoded: &[u8]) -> Option<Verifiab
 
 #[cfg(test)]
 mod tests {
-    use super::{decode_block_header, encode_block_header};
-    use crate::test_helpers::create_test_block_header_london; // Adjust import as needed
+    use super::{decode_block_header, encode_block_header, verify_block, VerifiableBlockHeader};
+    use crate::constants::{DENCUN_START, LONDON_START, PARIS_START, PRAGUE_START, SHAPELLA_START};
+    use crate::proofs::keccak256;
+    use crate::test_helpers::{
+        create_test_block_header_london, create_test_block_header_paris,
+        create_test_block_header_shapella,
+    };
+    use primitive_types::H256;
+
+    /// Mainnet block 1.
+    const BLOCK_1_RLP: &str = "f90211a0d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d493479405a56e2d52c817161883f50c441c3228cfe54d9fa0d67e4d450343046425ae4271474353857ab860dbc0a1dde64b41b5cd3a532bf3a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b90100000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008503ff80000001821388808455ba422499476574682f76312e302e302f6c696e75782f676f312e342e32a0969b900de27b6ac6a67742365dd65f55a0526c41fd18e1b16f1a1215c2e66f5988539bd4979fef1ec4";
+    const BLOCK_1_HASH: &str = "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6";
+
+    #[test]
+    fn test_decoded_headers_round_trip() {
+        // One header per era: decoding gives a database header that verifies and encodes back to
+        // the same bytes.
+        let shapella = VerifiableBlockHeader {
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+            ..create_test_block_header_shapella()
+        };
+        let prague = VerifiableBlockHeader {
+            requests_hash: Some(format!("{:?}", H256::repeat_byte(0xe3))),
+            ..create_test_block_header_shapella()
+        };
+        let mut headers = vec![(1, hex::decode(BLOCK_1_RLP).unwrap())];
+        for (number, block_header) in [
+            (LONDON_START, create_test_block_header_london()),
+            (PARIS_START, create_test_block_header_paris()),
+            (SHAPELLA_START, shapella),
+            (DENCUN_START, create_test_block_header_shapella()),
+            (PRAGUE_START, prague),
+        ] {
+            let block_header = VerifiableBlockHeader {
+                number: number as i64,
+                ..block_header
+            };
+            headers.push((number, encode_block_header(number, block_header).unwrap()));
+        }
+        assert_eq!(format!("{:?}", keccak256(&headers[0].1)), BLOCK_1_HASH);
+
+        for (number, encoded) in headers {
+            let block_header = decode_block_header(number, &encoded).unwrap();
+            let block_hash = format!("{:?}", keccak256(&encoded));
+            assert!(
+                verify_block(number, block_header.clone(), &block_hash).unwrap(),
+                "block {number}"
+            );
+            assert_eq!(encode_block_header(number, block_header), Some(encoded));
+        }
+    }
 
     #[test]
     #[ignore]
@@ -0,0 +1,330 @@
+use crate::proofs::keccak256;
+use eth_rlp_types::BlockHeaderTrait;
+use primitive_types::H256;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MmrError {
+    #[error("Leaf index {index} is out of range for an MMR with {leaf_count} leaves")]
+    LeafIndexOutOfRange { index: u64, leaf_count: u64 },
+}
+
+/// A hash function used to combine nodes of a Merkle Mountain Range.
+///
+/// The same function is used to merge two subtrees into their parent and to commit to the MMR
+/// size and its bagged peaks in the root, so that any 2-to-1 hash (Keccak256, Poseidon, ...) can
+/// back the accumulator.
+pub trait MmrHasher {
+    /// Hashes two nodes into their parent.
+    fn hash_pair(&self, left: &H256, right: &H256) -> H256;
+}
+
+/// The Keccak256 MMR hasher, computing `keccak256(left ++ right)`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Keccak256Hasher;
+
+impl MmrHasher for Keccak256Hasher {
+    fn hash_pair(&self, left: &H256, right: &H256) -> H256 {
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(left.as_bytes());
+        data[32..].copy_from_slice(right.as_bytes());
+        keccak256(&data)
+    }
+}
+
+/// Returns the heights of the perfect trees making up an MMR with `leaf_count` leaves, from the
+/// leftmost (tallest) one to the rightmost.
+fn peak_heights(leaf_count: u64) -> impl Iterator<Item = u32> {
+    (0..u64::BITS)
+        .rev()
+        .filter(move |height| leaf_count & (1 << height) != 0)
+}
+
+/// Number of nodes in a perfect binary tree with `2^height` leaves.
+fn tree_size(height: u32) -> u64 {
+    (1 << (height + 1)) - 1
+}
+
+/// Bags the peaks from right to left and commits to the number of leaves.
+fn bag_peaks<H: MmrHasher>(hasher: &H, leaf_count: u64, peaks: &[H256]) -> H256 {
+    let bagged = peaks
+        .iter()
+        .rev()
+        .copied()
+        .reduce(|acc, peak| hasher.hash_pair(&peak, &acc))
+        .unwrap_or_default();
+    hasher.hash_pair(&H256::from_low_u64_be(leaf_count), &bagged)
+}
+
+/// A Merkle Mountain Range accumulating block hashes.
+///
+/// Leaves are appended in order and never modified, which makes the MMR a good fit for
+/// committing to a growing sequence of verified block hashes. All nodes are kept in memory in
+/// post-order, so that inclusion proofs can be generated for any leaf.
+///
+/// # Fields
+///
+/// - `hasher`: The hash function used to merge nodes.
+/// - `nodes`: Every node of the MMR, in post-order.
+/// - `leaf_count`: The number of leaves appended so far.
+#[derive(Clone, Debug, Default)]
+pub struct Mmr<H: MmrHasher = Keccak256Hasher> {
+    hasher: H,
+    nodes: Vec<H256>,
+    leaf_count: u64,
+}
+
+/// A proof that a leaf is included in an MMR of a given size.
+///
+/// # Fields
+///
+/// - `leaf_index`: The position of the leaf among all leaves, starting at 0.
+/// - `leaf_count`: The number of leaves in the MMR the proof was generated for.
+/// - `siblings`: The sibling nodes on the path from the leaf to its peak, bottom-up.
+/// - `peaks`: All peaks of the MMR, from left to right.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MmrProof {
+    pub leaf_index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<H256>,
+    pub peaks: Vec<H256>,
+}
+
+impl Mmr<Keccak256Hasher> {
+    /// Creates an empty MMR backed by Keccak256.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<H: MmrHasher> Mmr<H> {
+    /// Creates an empty MMR backed by the given hash function.
+    pub fn with_hasher(hasher: H) -> Self {
+        Mmr {
+            hasher,
+            nodes: vec![],
+            leaf_count: 0,
+        }
+    }
+
+    /// Returns the number of leaves appended so far.
+    pub fn leaf_count(&self) -> u64 {
+        self.leaf_count
+    }
+
+    /// Returns the total number of nodes, leaves included.
+    pub fn size(&self) -> u64 {
+        self.nodes.len() as u64
+    }
+
+    /// Appends a leaf, merging the perfect trees that now have a sibling of the same height.
+    ///
+    /// # Returns
+    ///
+    /// The index of the appended leaf.
+    pub fn append(&mut self, leaf: H256) -> u64 {
+        let leaf_index = self.leaf_count;
+        self.nodes.push(leaf);
+
+        // Every trailing one bit of the leaf index is a complete left sibling to merge with.
+        for height in 0..leaf_index.trailing_ones() {
+            let right = self.nodes[self.nodes.len() - 1];
+            let left = self.nodes[self.nodes.len() - 1 - tree_size(height) as usize];
+            self.nodes.push(self.hasher.hash_pair(&left, &right));
+        }
+
+        self.leaf_count += 1;
+        leaf_index
+    }
+
+    /// Appends the hash of a block header, as computed by `BlockHeaderTrait::compute_hash`.
+    ///
+    /// # Returns
+    ///
+    /// The index of the appended leaf.
+    pub fn append_header<T: BlockHeaderTrait>(&mut self, block_header: &T) -> u64 {
+        self.append(block_header.compute_hash())
+    }
+
+    /// Returns the roots of the perfect trees making up the MMR, from left to right.
+    pub fn peaks(&self) -> Vec<H256> {
+        let mut offset = 0;
+        peak_heights(self.leaf_count)
+            .map(|height| {
+                offset += tree_size(height);
+                self.nodes[offset as usize - 1]
+            })
+            .collect()
+    }
+
+    /// Returns the root of the MMR, committing to its peaks and number of leaves.
+    pub fn root(&self) -> H256 {
+        bag_peaks(&self.hasher, self.leaf_count, &self.peaks())
+    }
+
+    /// Generates an inclusion proof for the leaf at `leaf_index`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<MmrProof, MmrError>` that can be checked against `root` with `verify_mmr_proof`.
+    pub fn proof(&self, leaf_index: u64) -> Result<MmrProof, MmrError> {
+        if leaf_index >= self.leaf_count {
+            return Err(MmrError::LeafIndexOutOfRange {
+                index: leaf_index,
+                leaf_count: self.leaf_count,
+            });
+        }
+
+        let mut offset = 0;
+        let mut first_leaf = 0;
+        let mut siblings = vec![];
+        for height in peak_heights(self.leaf_count) {
+            if leaf_index >= first_leaf + (1 << height) {
+                offset += tree_size(height);
+                first_leaf += 1 << height;
+                continue;
+            }
+
+            // Walk down from the peak, recording the sibling of each node on the path.
+            let mut index = leaf_index - first_leaf;
+            for child_height in (0..height).rev() {
+                let left_root = offset + tree_size(child_height) - 1;
+                let right_root = left_root + tree_size(child_height);
+                if index < 1 << child_height {
+                    siblings.push(self.nodes[right_root as usize]);
+                } else {
+                    siblings.push(self.nodes[left_root as usize]);
+                    offset = left_root + 1;
+                    index -= 1 << child_height;
+                }
+            }
+            break;
+        }
+        siblings.reverse();
+
+        Ok(MmrProof {
+            leaf_index,
+            leaf_count: self.leaf_count,
+            siblings,
+            peaks: self.peaks(),
+        })
+    }
+}
+
+/// Verifies that `leaf` is included in the MMR with the given `root`.
+///
+/// The proof is checked without access to the MMR: the leaf is hashed up to its peak with the
+/// provided siblings, and the peaks are bagged and compared with `root`.
+///
+/// # Arguments
+///
+/// - `hasher`: The hash function the MMR was built with.
+/// - `root`: The trusted MMR root.
+/// - `leaf`: The leaf value, e.g. a verified block hash.
+/// - `proof`: The proof generated by `Mmr::proof`.
+///
+/// # Returns
+///
+/// A `bool` indicating whether the proof is valid.
+pub fn verify_mmr_proof<H: MmrHasher>(
+    hasher: &H,
+    root: H256,
+    leaf: H256,
+    proof: &MmrProof,
+) -> bool {
+    if proof.leaf_index >= proof.leaf_count
+        || proof.peaks.len() != proof.leaf_count.count_ones() as usize
+    {
+        return false;
+    }
+
+    let mut first_leaf = 0;
+    for (peak_index, height) in peak_heights(proof.leaf_count).enumerate() {
+        if proof.leaf_index >= first_leaf + (1 << height) {
+            first_leaf += 1 << height;
+            continue;
+        }
+        if proof.siblings.len() != height as usize {
+            return false;
+        }
+
+        let index = proof.leaf_index - first_leaf;
+        let peak = proof
+            .siblings
+            .iter()
+            .enumerate()
+            .fold(leaf, |node, (level, sibling)| {
+                if (index >> level) & 1 == 1 {
+                    hasher.hash_pair(sibling, &node)
+                } else {
+                    hasher.hash_pair(&node, sibling)
+                }
+            });
+
+        return peak == proof.peaks[peak_index]
+            && bag_peaks(hasher, proof.leaf_count, &proof.peaks) == root;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mmr_with_leaves(count: u64) -> Mmr {
+        let mut mmr = Mmr::new();
+        for i in 0..count {
+            mmr.append(H256::from_low_u64_be(i));
+        }
+        mmr
+    }
+
+    #[test]
+    fn test_mmr_structure() {
+        let mmr = mmr_with_leaves(7);
+        assert_eq!(mmr.size(), 11);
+        assert_eq!(mmr.peaks().len(), 3);
+
+        let hasher = Keccak256Hasher;
+        let leaf = |i| H256::from_low_u64_be(i);
+        let pair = |a: H256, b: H256| hasher.hash_pair(&a, &b);
+        let first_peak = pair(pair(leaf(0), leaf(1)), pair(leaf(2), leaf(3)));
+        assert_eq!(
+            mmr.peaks(),
+            vec![first_peak, pair(leaf(4), leaf(5)), leaf(6)]
+        );
+    }
+
+    #[test]
+    fn test_mmr_proofs() {
+        let hasher = Keccak256Hasher;
+        for count in [1, 2, 7, 8, 33] {
+            let mmr = mmr_with_leaves(count);
+            let root = mmr.root();
+            for i in 0..count {
+                let proof = mmr.proof(i).unwrap();
+                assert!(verify_mmr_proof(
+                    &hasher,
+                    root,
+                    H256::from_low_u64_be(i),
+                    &proof
+                ));
+                assert!(!verify_mmr_proof(
+                    &hasher,
+                    root,
+                    H256::repeat_byte(0xff),
+                    &proof
+                ));
+            }
+        }
+
+        assert_eq!(
+            mmr_with_leaves(3).proof(3),
+            Err(MmrError::LeafIndexOutOfRange {
+                index: 3,
+                leaf_count: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,534 @@
+use crate::eras::{BlockHeaderDencun, BlockHeaderParis, BlockHeaderPrague, BlockHeaderShapella};
+use crate::proofs::{keccak256, ordered_trie_root};
+use eth_rlp_types::{BlockHeaderError, BlockHeaderTrait};
+use primitive_types::{H160, H256, U256};
+use rlp::RlpStream;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PayloadError {
+    #[error("Execution payload has no {0}")]
+    MissingField(&'static str),
+    #[error("Hex decoding error: {0}")]
+    HexDecodingError(#[from] hex::FromHexError),
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+}
+
+/// A withdrawal from the beacon chain, as found in `ExecutionPayloadV2` and later.
+///
+/// # Fields
+///
+/// - `index`: The global index of the withdrawal.
+/// - `validator_index`: The index of the withdrawing validator.
+/// - `address`: The execution address receiving the funds.
+/// - `amount`: The withdrawn amount, in Gwei.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    pub index: String,
+    pub validator_index: String,
+    pub address: String,
+    pub amount: String,
+}
+
+/// An execution payload as returned by the Engine API and the beacon API.
+///
+/// The optional fields cover the payload versions: `withdrawals` is present from V2 (Shapella),
+/// and `blob_gas_used` and `excess_blob_gas` from V3 (Dencun). V4 (Prague) payloads share the V3
+/// layout, their execution requests being passed alongside the payload like the parent beacon
+/// block root.
+///
+/// # Fields
+///
+/// All values are kept as the hex strings found in JSON payloads. `transactions` holds the raw
+/// transactions as included in the block, i.e. typed transactions with their type prefix.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayload {
+    pub parent_hash: String,
+    pub fee_recipient: String,
+    pub state_root: String,
+    pub receipts_root: String,
+    pub logs_bloom: String,
+    pub prev_randao: String,
+    pub block_number: String,
+    pub gas_limit: String,
+    pub gas_used: String,
+    pub timestamp: String,
+    pub extra_data: String,
+    pub base_fee_per_gas: String,
+    pub block_hash: String,
+    pub transactions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<String>,
+}
+
+pub(crate) fn decode_hex(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+}
+
+pub(crate) fn parse_h256(value: &str) -> Result<H256, BlockHeaderError> {
+    Ok(H256::from_str(value)?)
+}
+
+pub(crate) fn parse_h160(value: &str) -> Result<H160, BlockHeaderError> {
+    Ok(H160::from_str(value)?)
+}
+
+pub(crate) fn parse_quantity(value: &str) -> Result<U256, BlockHeaderError> {
+    Ok(U256::from_str(value)?)
+}
+
+/// Computes the `requests_hash` of a block from its execution requests, as defined by EIP-7685.
+///
+/// # Arguments
+///
+/// - `execution_requests`: The requests, each prefixed with its request type. Requests without
+///   data are skipped.
+///
+/// # Returns
+///
+/// The `H256` commitment `sha256(sha256(request_0) ++ sha256(request_1) ++ ...)`.
+pub fn requests_hash(execution_requests: &[Vec<u8>]) -> H256 {
+    let mut hasher = Sha256::new();
+    for request in execution_requests
+        .iter()
+        .filter(|request| request.len() > 1)
+    {
+        hasher.update(Sha256::digest(request));
+    }
+    H256::from_slice(&hasher.finalize())
+}
+
+impl ExecutionPayload {
+    /// Computes the root of the transactions trie of the payload.
+    pub fn transactions_root(&self) -> Result<H256, PayloadError> {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|transaction| decode_hex(transaction))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ordered_trie_root(&transactions))
+    }
+
+    /// Computes the root of the withdrawals trie of the payload.
+    ///
+    /// # Returns
+    ///
+    /// `None` for V1 payloads, which carry no withdrawals.
+    pub fn withdrawals_root(&self) -> Result<Option<H256>, PayloadError> {
+        let Some(withdrawals) = &self.withdrawals else {
+            return Ok(None);
+        };
+
+        let encoded = withdrawals
+            .iter()
+            .map(|withdrawal| {
+                let mut stream = RlpStream::new_list(4);
+                stream.append(&parse_quantity(&withdrawal.index)?);
+                stream.append(&parse_quantity(&withdrawal.validator_index)?);
+                stream.append(&parse_h160(&withdrawal.address)?);
+                stream.append(&parse_quantity(&withdrawal.amount)?);
+                Ok(stream.out().to_vec())
+            })
+            .collect::<Result<Vec<_>, BlockHeaderError>>()?;
+        Ok(Some(ordered_trie_root(&encoded)))
+    }
+
+    /// Converts a V1 payload into a Paris block header.
+    ///
+    /// The fields removed by the merge are set to their post-merge constants: no ommers, zero
+    /// difficulty and nonce, and `prev_randao` in place of the mix hash.
+    pub fn to_paris_header(&self) -> Result<BlockHeaderParis, PayloadError> {
+        Ok(BlockHeaderParis {
+            parent_hash: parse_h256(&self.parent_hash)?,
+            ommers_hash: keccak256(&rlp::EMPTY_LIST_RLP),
+            beneficiary: parse_h160(&self.fee_recipient)?,
+            state_root: parse_h256(&self.state_root)?,
+            transactions_root: self.transactions_root()?,
+            receipts_root: parse_h256(&self.receipts_root)?,
+            logs_bloom: <BlockHeaderParis as BlockHeaderTrait>::hex_to_fixed_array::<256>(
+                &self.logs_bloom,
+            )?,
+            difficulty: U256::zero(),
+            number: parse_quantity(&self.block_number)?,
+            gas_limit: parse_quantity(&self.gas_limit)?,
+            gas_used: parse_quantity(&self.gas_used)?,
+            timestamp: parse_quantity(&self.timestamp)?,
+            extra_data: decode_hex(&self.extra_data)?,
+            mix_hash: parse_h256(&self.prev_randao)?,
+            nonce: [0; 8],
+            base_fee_per_gas: parse_quantity(&self.base_fee_per_gas)?,
+        })
+    }
+
+    /// Converts a V2 payload into a Shapella block header.
+    pub fn to_shapella_header(&self) -> Result<BlockHeaderShapella, PayloadError> {
+        let withdrawals_root = self
+            .withdrawals_root()?
+            .ok_or(PayloadError::MissingField("withdrawals"))?;
+        let BlockHeaderParis {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+        } = self.to_paris_header()?;
+
+        Ok(BlockHeaderShapella {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+            withdrawals_root,
+        })
+    }
+
+    /// Converts a V3 payload into a Dencun block header.
+    ///
+    /// # Arguments
+    ///
+    /// - `parent_beacon_block_root`: The root passed alongside the payload to `engine_newPayloadV3`.
+    pub fn to_dencun_header(
+        &self,
+        parent_beacon_block_root: H256,
+    ) -> Result<BlockHeaderDencun, PayloadError> {
+        let blob_gas_used = parse_quantity(
+            self.blob_gas_used
+                .as_deref()
+                .ok_or(PayloadError::MissingField("blobGasUsed"))?,
+        )?;
+        let excess_blob_gas = parse_quantity(
+            self.excess_blob_gas
+                .as_deref()
+                .ok_or(PayloadError::MissingField("excessBlobGas"))?,
+        )?;
+        let BlockHeaderShapella {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+            withdrawals_root,
+        } = self.to_shapella_header()?;
+
+        Ok(BlockHeaderDencun {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+            withdrawals_root,
+            parent_beacon_block_root,
+            blob_gas_used,
+            excess_blob_gas,
+        })
+    }
+
+    /// Converts a V4 payload into a Prague block header.
+    ///
+    /// # Arguments
+    ///
+    /// - `parent_beacon_block_root`: The root passed alongside the payload to `engine_newPayloadV4`.
+    /// - `execution_requests`: The hex encoded, type prefixed requests passed alongside the payload.
+    pub fn to_prague_header(
+        &self,
+        parent_beacon_block_root: H256,
+        execution_requests: &[String],
+    ) -> Result<BlockHeaderPrague, PayloadError> {
+        let execution_requests = execution_requests
+            .iter()
+            .map(|request| decode_hex(request))
+            .collect::<Result<Vec<_>, _>>()?;
+        let BlockHeaderDencun {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+            withdrawals_root,
+            parent_beacon_block_root,
+            blob_gas_used,
+            excess_blob_gas,
+        } = self.to_dencun_header(parent_beacon_block_root)?;
+
+        Ok(BlockHeaderPrague {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            extra_data,
+            mix_hash,
+            nonce,
+            base_fee_per_gas,
+            withdrawals_root,
+            parent_beacon_block_root,
+            blob_gas_used,
+            excess_blob_gas,
+            requests_hash: requests_hash(&execution_requests),
+        })
+    }
+
+    /// Computes the hash of the block described by the payload.
+    ///
+    /// The header type is picked from the payload version: execution requests make it a Prague
+    /// header, blob gas fields a Dencun header, and withdrawals a Shapella header.
+    ///
+    /// # Arguments
+    ///
+    /// - `parent_beacon_block_root`: The parent beacon block root, required from V3 on.
+    /// - `execution_requests`: The execution requests of V4 payloads.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<H256, PayloadError>` containing the computed block hash.
+    pub fn compute_block_hash(
+        &self,
+        parent_beacon_block_root: Option<H256>,
+        execution_requests: Option<&[String]>,
+    ) -> Result<H256, PayloadError> {
+        let missing_root = PayloadError::MissingField("parentBeaconBlockRoot");
+        if let Some(execution_requests) = execution_requests {
+            let root = parent_beacon_block_root.ok_or(missing_root)?;
+            Ok(self
+                .to_prague_header(root, execution_requests)?
+                .compute_hash())
+        } else if self.blob_gas_used.is_some() || self.excess_blob_gas.is_some() {
+            let root = parent_beacon_block_root.ok_or(missing_root)?;
+            Ok(self.to_dencun_header(root)?.compute_hash())
+        } else if self.withdrawals.is_some() {
+            Ok(self.to_shapella_header()?.compute_hash())
+        } else {
+            Ok(self.to_paris_header()?.compute_hash())
+        }
+    }
+
+    /// Verifies the `block_hash` claimed by the payload against the hash of its header.
+    ///
+    /// # Arguments
+    ///
+    /// - `parent_beacon_block_root`: The parent beacon block root, required from V3 on.
+    /// - `execution_requests`: The execution requests of V4 payloads.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<bool, PayloadError>` indicating whether the claimed hash is correct.
+    pub fn verify_block_hash(
+        &self,
+        parent_beacon_block_root: Option<H256>,
+        execution_requests: Option<&[String]>,
+    ) -> Result<bool, PayloadError> {
+        let computed = self.compute_block_hash(parent_beacon_block_root, execution_requests)?;
+        Ok(computed == parse_h256(&self.block_hash)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::PRAGUE_START;
+    use crate::proofs::EMPTY_TRIE_ROOT;
+    use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+
+    fn mock_payload() -> ExecutionPayload {
+        serde_json::from_value(serde_json::json!({
+            "parentHash": format!("{:?}", H256::repeat_byte(0x11)),
+            "feeRecipient": format!("{:?}", H160::repeat_byte(0x22)),
+            "stateRoot": format!("{:?}", H256::repeat_byte(0x33)),
+            "receiptsRoot": format!("{:?}", H256::repeat_byte(0x44)),
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "prevRandao": format!("{:?}", H256::repeat_byte(0x55)),
+            "blockNumber": "0x1312d00",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x5208",
+            "timestamp": "0x6669d1a3",
+            "extraData": "0x6265617665726275696c642e6f7267",
+            "baseFeePerGas": "0x7",
+            "blockHash": format!("{:?}", H256::zero()),
+            "transactions": ["0x02f86b0180843b9aca00850c92a69c0082520894000000000000000000000000000000000000000080c0"],
+            "withdrawals": [{
+                "index": "0x1",
+                "validatorIndex": "0x2a",
+                "address": format!("{:?}", H160::repeat_byte(0x66)),
+                "amount": "0xf4240"
+            }],
+            "blobGasUsed": "0x20000",
+            "excessBlobGas": "0x0"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_payload_conversion() {
+        let payload = mock_payload();
+        let header = payload.to_dencun_header(H256::repeat_byte(0x77)).unwrap();
+
+        assert_eq!(header.number, U256::from(20_000_000));
+        assert_eq!(header.mix_hash, H256::repeat_byte(0x55));
+        assert_eq!(header.blob_gas_used, U256::from(131_072));
+        assert_eq!(header.extra_data, b"beaverbuild.org".to_vec());
+        assert_eq!(
+            format!("{:?}", header.ommers_hash),
+            "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+        );
+        assert_ne!(header.transactions_root, EMPTY_TRIE_ROOT);
+        assert_ne!(header.withdrawals_root, EMPTY_TRIE_ROOT);
+
+        let v1 = ExecutionPayload {
+            withdrawals: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            ..payload.clone()
+        };
+        assert!(matches!(
+            v1.to_shapella_header(),
+            Err(PayloadError::MissingField("withdrawals"))
+        ));
+        assert!(matches!(
+            payload.compute_block_hash(None, None),
+            Err(PayloadError::MissingField("parentBeaconBlockRoot"))
+        ));
+    }
+
+    #[test]
+    fn test_verify_payload_block_hash() {
+        let mut payload = mock_payload();
+        payload.block_number = format!("{PRAGUE_START:#x}");
+        let root = H256::repeat_byte(0x77);
+        let requests = vec!["0x00".to_string(), "0x01aabb".to_string()];
+
+        // The expected hash is computed by the Prague encoder of database headers rather than by
+        // the payload conversion under test.
+        let block_header = VerifiableBlockHeader {
+            block_hash: String::new(),
+            number: PRAGUE_START as i64,
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            nonce: "0x0000000000000000".to_string(),
+            transaction_root: Some(format!("{:?}", payload.transactions_root().unwrap())),
+            receipts_root: Some(payload.receipts_root.clone()),
+            state_root: Some(payload.state_root.clone()),
+            base_fee_per_gas: Some(payload.base_fee_per_gas.clone()),
+            parent_hash: Some(payload.parent_hash.clone()),
+            ommers_hash: None,
+            miner: Some(payload.fee_recipient.clone()),
+            logs_bloom: Some(payload.logs_bloom.clone()),
+            difficulty: Some("0x0".to_string()),
+            totaldifficulty: None,
+            sha3_uncles: Some(
+                "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347".to_string(),
+            ),
+            timestamp: Some(payload.timestamp.clone()),
+            extra_data: Some(payload.extra_data.clone()),
+            mix_hash: Some(payload.prev_randao.clone()),
+            withdrawals_root: Some(format!(
+                "{:?}",
+                payload.withdrawals_root().unwrap().unwrap()
+            )),
+            blob_gas_used: payload.blob_gas_used.clone(),
+            excess_blob_gas: payload.excess_blob_gas.clone(),
+            parent_beacon_block_root: Some(format!("{root:?}")),
+            // sha256(sha256(0x01aabb)), the empty deposit requests being skipped.
+            requests_hash: Some(format!(
+                "{:?}",
+                H256::from_slice(&Sha256::digest(Sha256::digest([0x01, 0xaa, 0xbb])))
+            )),
+        };
+        let encoded = crate::encode_block_header(PRAGUE_START, block_header).unwrap();
+        payload.block_hash = format!("{:?}", keccak256(&encoded));
+
+        assert!(payload
+            .verify_block_hash(Some(root), Some(&requests))
+            .unwrap());
+        assert!(!payload.verify_block_hash(Some(root), Some(&[])).unwrap());
+        assert!(!payload.verify_block_hash(Some(root), None).unwrap());
+
+        payload.gas_used = "0x5209".to_string();
+        assert!(!payload
+            .verify_block_hash(Some(root), Some(&requests))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_requests_hash() {
+        // The requests hash of a block without requests, sha256(b"").
+        let empty =
+            H256::from_str("0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+                .unwrap();
+        assert_eq!(requests_hash(&[]), empty);
+        assert_eq!(requests_hash(&[vec![0x00], vec![0x01]]), empty);
+        assert_ne!(requests_hash(&[vec![0x00, 0x01]]), empty);
+    }
+}
@@ -0,0 +1,348 @@
+use super::mpt::{keccak256, verify_proof, EMPTY_TRIE_ROOT};
+use super::{decode_proof_nodes, ProofError};
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError};
+use primitive_types::{H160, H256, U256};
+use rlp::Rlp;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The code hash of accounts without code, `keccak256("")`.
+pub const EMPTY_CODE_HASH: H256 = H256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+/// An account proof in the shape returned by the `eth_getProof` JSON-RPC method (EIP-1186).
+///
+/// Like `eth_rlp_types::BlockHeader`, all values are kept as the hexadecimal strings found in the
+/// response, and are only parsed during verification.
+///
+/// # Fields
+///
+/// - `address`: The address of the account.
+/// - `balance`: The claimed balance of the account.
+/// - `code_hash`: The claimed Keccak256 hash of the account code.
+/// - `nonce`: The claimed nonce of the account.
+/// - `storage_hash`: The claimed root of the account storage trie.
+/// - `account_proof`: The RLP-encoded state trie nodes from the state root to the account.
+/// - `storage_proof`: The proofs for the requested storage slots.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: String,
+    pub balance: String,
+    pub code_hash: String,
+    pub nonce: String,
+    pub storage_hash: String,
+    pub account_proof: Vec<String>,
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// A storage slot proof in the shape returned by the `eth_getProof` JSON-RPC method.
+///
+/// # Fields
+///
+/// - `key`: The storage slot, which may be shorter than 32 bytes (e.g. `0x0`).
+/// - `value`: The claimed value stored in the slot.
+/// - `proof`: The RLP-encoded storage trie nodes from the storage root to the slot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct StorageProof {
+    pub key: String,
+    pub value: String,
+    pub proof: Vec<String>,
+}
+
+/// An account whose state has been proven against a state root.
+///
+/// Accounts that are proven to be absent from the state trie (`exists == false`) carry the values
+/// of an empty account: zero nonce and balance, the empty storage root and the empty code hash.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedAccount {
+    pub address: H160,
+    pub exists: bool,
+    pub nonce: U256,
+    pub balance: U256,
+    pub storage_root: H256,
+    pub code_hash: H256,
+    pub storage: Vec<VerifiedStorageSlot>,
+}
+
+/// A storage slot whose value has been proven against an account storage root.
+///
+/// Slots proven to be absent from the storage trie (`exists == false`) have a zero value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedStorageSlot {
+    pub key: H256,
+    pub exists: bool,
+    pub value: U256,
+}
+
+/// Verifies an `eth_getProof` response against the state root of a verified block header.
+///
+/// The header is expected to have already passed `verify_block`, so that its `state_root` can be
+/// trusted. The account proof and every storage proof are checked, and the values claimed in the
+/// response are compared with the proven ones.
+///
+/// # Arguments
+///
+/// - `block_header`: The verified block header whose `state_root` the proof is checked against.
+/// - `account_proof`: The `eth_getProof` response for the account.
+///
+/// # Returns
+///
+/// A `Result<VerifiedAccount, ProofError>` containing the proven account state and storage slots.
+pub fn verify_account_proof(
+    block_header: &VerifiableBlockHeader,
+    account_proof: &AccountProof,
+) -> Result<VerifiedAccount, ProofError> {
+    let state_root = block_header
+        .state_root
+        .as_deref()
+        .ok_or(ProofError::MissingRoot("state_root"))?;
+    let state_root = H256::from_str(state_root).map_err(BlockHeaderError::from)?;
+
+    verify_account_proof_with_root(state_root, account_proof)
+}
+
+/// Verifies an `eth_getProof` response against the given state root.
+///
+/// See `verify_account_proof` for details.
+pub fn verify_account_proof_with_root(
+    state_root: H256,
+    account_proof: &AccountProof,
+) -> Result<VerifiedAccount, ProofError> {
+    let address = H160::from_str(&account_proof.address).map_err(BlockHeaderError::from)?;
+    let path = keccak256(address.as_bytes());
+    let nodes = decode_proof_nodes(&account_proof.account_proof)?;
+
+    let account = match verify_proof(state_root, path.as_bytes(), &nodes)? {
+        Some(value) => {
+            let rlp = Rlp::new(&value);
+            VerifiedAccount {
+                address,
+                exists: true,
+                nonce: rlp.val_at(0)?,
+                balance: rlp.val_at(1)?,
+                storage_root: rlp.val_at(2)?,
+                code_hash: rlp.val_at(3)?,
+                storage: vec![],
+            }
+        }
+        None => VerifiedAccount {
+            address,
+            exists: false,
+            nonce: U256::zero(),
+            balance: U256::zero(),
+            storage_root: EMPTY_TRIE_ROOT,
+            code_hash: EMPTY_CODE_HASH,
+            storage: vec![],
+        },
+    };
+
+    check_claim(
+        "nonce",
+        U256::from_str(&account_proof.nonce).map_err(BlockHeaderError::from)?,
+        account.nonce,
+    )?;
+    check_claim(
+        "balance",
+        U256::from_str(&account_proof.balance).map_err(BlockHeaderError::from)?,
+        account.balance,
+    )?;
+    check_hash_claim(
+        "storageHash",
+        &account_proof.storage_hash,
+        account.storage_root,
+        account.exists,
+    )?;
+    check_hash_claim(
+        "codeHash",
+        &account_proof.code_hash,
+        account.code_hash,
+        account.exists,
+    )?;
+
+    let storage = account_proof
+        .storage_proof
+        .iter()
+        .map(|storage_proof| verify_storage_proof(account.storage_root, storage_proof))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(VerifiedAccount { storage, ..account })
+}
+
+/// Verifies a single storage slot proof against an account storage root.
+///
+/// # Arguments
+///
+/// - `storage_root`: The proven storage root of the account.
+/// - `storage_proof`: The `eth_getProof` storage proof entry for the slot.
+///
+/// # Returns
+///
+/// A `Result<VerifiedStorageSlot, ProofError>` containing the proven slot value.
+pub fn verify_storage_proof(
+    storage_root: H256,
+    storage_proof: &StorageProof,
+) -> Result<VerifiedStorageSlot, ProofError> {
+    let key = parse_storage_key(&storage_proof.key)?;
+    let path = keccak256(key.as_bytes());
+    let nodes = decode_proof_nodes(&storage_proof.proof)?;
+
+    let slot = match verify_proof(storage_root, path.as_bytes(), &nodes)? {
+        Some(value) => VerifiedStorageSlot {
+            key,
+            exists: true,
+            value: rlp::decode(&value)?,
+        },
+        None => VerifiedStorageSlot {
+            key,
+            exists: false,
+            value: U256::zero(),
+        },
+    };
+
+    let claimed = U256::from_str(&storage_proof.value).map_err(BlockHeaderError::from)?;
+    check_claim("storageProof.value", claimed, slot.value)?;
+
+    Ok(slot)
+}
+
+/// Parses a storage slot, left-padding keys shorter than 32 bytes.
+fn parse_storage_key(key: &str) -> Result<H256, ProofError> {
+    let content = key.strip_prefix("0x").unwrap_or(key);
+    let padded = format!("{:0>64}", content);
+    if padded.len() > 64 {
+        return Err(BlockHeaderError::InvalidInputLength {
+            expected: 32,
+            got: padded.len().div_ceil(2),
+        }
+        .into());
+    }
+
+    Ok(H256::from_slice(&hex::decode(padded)?))
+}
+
+fn check_claim<T: PartialEq>(field: &'static str, claimed: T, proven: T) -> Result<(), ProofError> {
+    if claimed != proven {
+        return Err(ProofError::ClaimMismatch(field));
+    }
+    Ok(())
+}
+
+/// Compares a claimed root or hash with the proven one.
+///
+/// Some clients report zero instead of the empty root and code hash for absent accounts, so
+/// a zero claim is accepted in that case.
+fn check_hash_claim(
+    field: &'static str,
+    claimed: &str,
+    proven: H256,
+    exists: bool,
+) -> Result<(), ProofError> {
+    let claimed = H256::from_str(claimed).map_err(BlockHeaderError::from)?;
+    if !exists && claimed.is_zero() {
+        return Ok(());
+    }
+    check_claim(field, claimed, proven)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlp::RlpStream;
+
+    /// Builds a trie holding a single leaf and returns its root and the leaf node.
+    fn single_leaf_trie(path: H256, value: &[u8]) -> (H256, Vec<u8>) {
+        let mut encoded_path = vec![0x20];
+        encoded_path.extend_from_slice(path.as_bytes());
+
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&encoded_path);
+        stream.append(&value);
+        let leaf = stream.out().to_vec();
+        (keccak256(&leaf), leaf)
+    }
+
+    fn mock_account_proof() -> (H256, AccountProof) {
+        let address = H160::repeat_byte(0x11);
+        let slot = H256::from_low_u64_be(1);
+
+        let (storage_root, storage_leaf) =
+            single_leaf_trie(keccak256(slot.as_bytes()), &rlp::encode(&U256::from(42)));
+
+        let mut account = RlpStream::new_list(4);
+        account.append(&U256::from(7));
+        account.append(&U256::from(1_000_000));
+        account.append(&storage_root);
+        account.append(&EMPTY_CODE_HASH);
+        let (state_root, account_leaf) =
+            single_leaf_trie(keccak256(address.as_bytes()), &account.out());
+
+        let json = format!(
+            r#"{{
+                "address": "{address:?}",
+                "balance": "0xf4240",
+                "codeHash": "{EMPTY_CODE_HASH:?}",
+                "nonce": "0x7",
+                "storageHash": "{storage_root:?}",
+                "accountProof": ["0x{}"],
+                "storageProof": [
+                    {{ "key": "0x1", "value": "0x2a", "proof": ["0x{}"] }},
+                    {{ "key": "0x2", "value": "0x0", "proof": ["0x{}"] }}
+                ]
+            }}"#,
+            hex::encode(&account_leaf),
+            hex::encode(&storage_leaf),
+            hex::encode(&storage_leaf),
+        );
+        (state_root, serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn test_verify_account_and_storage_proofs() {
+        let (state_root, account_proof) = mock_account_proof();
+        let verified = verify_account_proof_with_root(state_root, &account_proof).unwrap();
+
+        assert!(verified.exists);
+        assert_eq!(verified.nonce, U256::from(7));
+        assert_eq!(verified.balance, U256::from(1_000_000));
+        assert_eq!(verified.code_hash, EMPTY_CODE_HASH);
+        assert_eq!(verified.storage.len(), 2);
+        assert_eq!(verified.storage[0].value, U256::from(42));
+        assert!(verified.storage[0].exists);
+        assert_eq!(verified.storage[1].value, U256::zero());
+        assert!(!verified.storage[1].exists);
+    }
+
+    #[test]
+    fn test_verify_account_exclusion_proof() {
+        let (state_root, mut account_proof) = mock_account_proof();
+        account_proof.address = format!("{:?}", H160::repeat_byte(0x22));
+        account_proof.balance = "0x0".to_string();
+        account_proof.nonce = "0x0".to_string();
+        account_proof.code_hash = format!("{:?}", H256::zero());
+        account_proof.storage_hash = format!("{:?}", EMPTY_TRIE_ROOT);
+        account_proof.storage_proof = vec![StorageProof {
+            key: "0x1".to_string(),
+            value: "0x0".to_string(),
+            proof: vec![],
+        }];
+
+        let verified = verify_account_proof_with_root(state_root, &account_proof).unwrap();
+        assert!(!verified.exists);
+        assert_eq!(verified.storage_root, EMPTY_TRIE_ROOT);
+        assert_eq!(verified.storage[0].value, U256::zero());
+    }
+
+    #[test]
+    fn test_verify_account_proof_rejects_wrong_claim() {
+        let (state_root, mut account_proof) = mock_account_proof();
+        account_proof.storage_proof[0].value = "0x2b".to_string();
+
+        assert!(matches!(
+            verify_account_proof_with_root(state_root, &account_proof),
+            Err(ProofError::ClaimMismatch("storageProof.value"))
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+use super::mpt::verify_proof;
+use super::trie::ordered_trie_proof;
+use super::ProofError;
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderError};
+use primitive_types::H256;
+use std::str::FromStr;
+
+/// Reads a trie root committed to by a block header field.
+fn header_root(root: Option<&str>, field: &'static str) -> Result<H256, ProofError> {
+    let root = root.ok_or(ProofError::MissingRoot(field))?;
+    Ok(H256::from_str(root).map_err(BlockHeaderError::from)?)
+}
+
+/// Generates a proof for the item at `index`, checking the list against the header's root.
+fn prove_item(
+    root: H256,
+    field: &'static str,
+    items: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<Vec<u8>>, ProofError> {
+    if index >= items.len() {
+        return Err(ProofError::IndexOutOfRange {
+            index,
+            len: items.len(),
+        });
+    }
+
+    let (computed_root, proof) = ordered_trie_proof(items, index);
+    if computed_root != root {
+        return Err(ProofError::RootMismatch(field));
+    }
+    Ok(proof)
+}
+
+/// Checks that `item` is stored at `index` in the ordered trie with the given root.
+fn verify_item(
+    root: H256,
+    index: usize,
+    item: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<bool, ProofError> {
+    let proven = verify_proof(root, &rlp::encode(&index), proof)?;
+    Ok(proven.as_deref() == Some(item))
+}
+
+/// Generates a proof that the transaction at `index` is included in a verified block.
+///
+/// The full list of the block's transactions is used to rebuild the transactions trie, and its
+/// root is checked against the header's `transaction_root` before the proof is returned.
+///
+/// # Arguments
+///
+/// - `block_header`: The verified block header the transactions belong to.
+/// - `transactions`: The block's transactions in their canonical encoding, i.e. the RLP list for
+///   legacy transactions and the type byte followed by the RLP payload for typed transactions.
+/// - `index`: The position of the transaction to prove.
+///
+/// # Returns
+///
+/// A `Result<Vec<Vec<u8>>, ProofError>` containing the RLP-encoded trie nodes of the proof.
+pub fn prove_transaction(
+    block_header: &VerifiableBlockHeader,
+    transactions: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<Vec<u8>>, ProofError> {
+    let root = header_root(block_header.transaction_root.as_deref(), "transaction_root")?;
+    prove_item(root, "transaction_root", transactions, index)
+}
+
+/// Verifies that `transaction` is the transaction at `index` in a verified block.
+///
+/// Only the proof nodes are needed; the block's other transactions are not.
+///
+/// # Arguments
+///
+/// - `block_header`: The verified block header whose `transaction_root` the proof is checked against.
+/// - `index`: The position of the transaction in the block.
+/// - `transaction`: The transaction in its canonical encoding.
+/// - `proof`: The RLP-encoded trie nodes, as produced by `prove_transaction`.
+///
+/// # Returns
+///
+/// A `Result<bool, ProofError>` indicating whether the transaction is included at `index`.
+pub fn verify_transaction_proof(
+    block_header: &VerifiableBlockHeader,
+    index: usize,
+    transaction: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<bool, ProofError> {
+    let root = header_root(block_header.transaction_root.as_deref(), "transaction_root")?;
+    verify_item(root, index, transaction, proof)
+}
+
+/// Generates a proof that the receipt at `index` is included in a verified block.
+///
+/// The full list of the block's receipts is used to rebuild the receipts trie, and its root is
+/// checked against the header's `receipts_root` before the proof is returned.
+///
+/// # Arguments
+///
+/// - `block_header`: The verified block header the receipts belong to.
+/// - `receipts`: The block's receipts in their canonical encoding, i.e. the RLP list for legacy
+///   receipts and the type byte followed by the RLP payload for typed receipts.
+/// - `index`: The position of the receipt to prove.
+///
+/// # Returns
+///
+/// A `Result<Vec<Vec<u8>>, ProofError>` containing the RLP-encoded trie nodes of the proof.
+pub fn prove_receipt(
+    block_header: &VerifiableBlockHeader,
+    receipts: &[Vec<u8>],
+    index: usize,
+) -> Result<Vec<Vec<u8>>, ProofError> {
+    let root = header_root(block_header.receipts_root.as_deref(), "receipts_root")?;
+    prove_item(root, "receipts_root", receipts, index)
+}
+
+/// Verifies that `receipt` is the receipt at `index` in a verified block.
+///
+/// Only the proof nodes are needed; the block's other receipts are not.
+///
+/// # Arguments
+///
+/// - `block_header`: The verified block header whose `receipts_root` the proof is checked against.
+/// - `index`: The position of the receipt in the block.
+/// - `receipt`: The receipt in its canonical encoding.
+/// - `proof`: The RLP-encoded trie nodes, as produced by `prove_receipt`.
+///
+/// # Returns
+///
+/// A `Result<bool, ProofError>` indicating whether the receipt is included at `index`.
+pub fn verify_receipt_proof(
+    block_header: &VerifiableBlockHeader,
+    index: usize,
+    receipt: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<bool, ProofError> {
+    let root = header_root(block_header.receipts_root.as_deref(), "receipts_root")?;
+    verify_item(root, index, receipt, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::trie::ordered_trie_root;
+    use super::*;
+    use crate::test_helpers::create_test_block_header_shapella;
+
+    #[test]
+    fn test_transaction_and_receipt_proofs() {
+        let transactions: Vec<Vec<u8>> = (0..20u8).map(|i| vec![0x02, i, i, i]).collect();
+        let receipts: Vec<Vec<u8>> = (0..20u8).map(|i| vec![0x02; 64 + i as usize]).collect();
+
+        let mut header = create_test_block_header_shapella();
+        header.transaction_root = Some(format!("{:?}", ordered_trie_root(&transactions)));
+        header.receipts_root = Some(format!("{:?}", ordered_trie_root(&receipts)));
+
+        let proof = prove_transaction(&header, &transactions, 7).unwrap();
+        assert!(verify_transaction_proof(&header, 7, &transactions[7], &proof).unwrap());
+        assert!(!verify_transaction_proof(&header, 8, &transactions[7], &proof).unwrap());
+
+        let proof = prove_receipt(&header, &receipts, 19).unwrap();
+        assert!(verify_receipt_proof(&header, 19, &receipts[19], &proof).unwrap());
+        assert!(!verify_receipt_proof(&header, 19, &receipts[18], &proof).unwrap());
+
+        assert!(matches!(
+            prove_receipt(&header, &receipts[1..], 0),
+            Err(ProofError::RootMismatch("receipts_root"))
+        ));
+        assert!(matches!(
+            prove_transaction(&header, &transactions, 20),
+            Err(ProofError::IndexOutOfRange { index: 20, len: 20 })
+        ));
+    }
+}
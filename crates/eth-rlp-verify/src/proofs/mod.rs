@@ -0,0 +1,49 @@
+mod account;
+mod inclusion;
+mod mpt;
+mod trie;
+
+use eth_rlp_types::BlockHeaderError;
+use thiserror::Error;
+
+pub use account::{
+    verify_account_proof, verify_account_proof_with_root, verify_storage_proof, AccountProof,
+    StorageProof, VerifiedAccount, VerifiedStorageSlot, EMPTY_CODE_HASH,
+};
+pub use inclusion::{
+    prove_receipt, prove_transaction, verify_receipt_proof, verify_transaction_proof,
+};
+pub use mpt::{keccak256, verify_proof, EMPTY_TRIE_ROOT};
+pub use trie::{ordered_trie_proof, ordered_trie_root};
+
+#[derive(Error, Debug)]
+pub enum ProofError {
+    #[error("Block header has no {0}")]
+    MissingRoot(&'static str),
+    #[error("Proof is missing a trie node")]
+    MissingNode,
+    #[error("Proof node {index} does not match the hash referenced by its parent")]
+    InvalidNodeHash { index: usize },
+    #[error("Invalid trie node")]
+    InvalidNode,
+    #[error("Index {index} is out of range for a list of {len} items")]
+    IndexOutOfRange { index: usize, len: usize },
+    #[error("Computed trie root does not match the header's {0}")]
+    RootMismatch(&'static str),
+    #[error("Claimed {0} does not match the proven value")]
+    ClaimMismatch(&'static str),
+    #[error("Rlp decoding error: {0}")]
+    RlpDecodingError(#[from] rlp::DecoderError),
+    #[error("Hex decoding error: {0}")]
+    HexDecodingError(#[from] hex::FromHexError),
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+}
+
+/// Decodes hexadecimal proof nodes, as found in JSON-RPC responses, into raw bytes.
+pub(crate) fn decode_proof_nodes(nodes: &[String]) -> Result<Vec<Vec<u8>>, ProofError> {
+    nodes
+        .iter()
+        .map(|node| Ok(hex::decode(node.strip_prefix("0x").unwrap_or(node))?))
+        .collect()
+}
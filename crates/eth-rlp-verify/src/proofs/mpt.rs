@@ -0,0 +1,234 @@
+use super::ProofError;
+use primitive_types::H256;
+use rlp::Rlp;
+use sha3::{Digest, Keccak256};
+
+/// The root of an empty Merkle-Patricia trie, `keccak256(rlp(""))`.
+///
+/// Accounts without storage commit to this value as their `storageRoot`, and blocks without
+/// transactions, receipts or withdrawals commit to it in the corresponding header fields.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Computes the Keccak256 hash of the given bytes.
+pub fn keccak256(data: &[u8]) -> H256 {
+    H256::from_slice(&Keccak256::digest(data))
+}
+
+/// A reference from one trie node to the next one along the proof path.
+///
+/// Children whose RLP encoding is at least 32 bytes long are referenced by their Keccak256 hash
+/// and appear as separate proof elements, while smaller children are embedded in their parent.
+enum NodeRef {
+    Hash(H256),
+    Inline(Vec<u8>),
+}
+
+impl NodeRef {
+    fn from_child(child: &Rlp) -> Result<Option<Self>, ProofError> {
+        if child.is_list() {
+            return Ok(Some(NodeRef::Inline(child.as_raw().to_vec())));
+        }
+
+        let data = child.data()?;
+        match data.len() {
+            0 => Ok(None),
+            32 => Ok(Some(NodeRef::Hash(H256::from_slice(data)))),
+            _ => Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// Splits a byte slice into its nibbles, most significant first.
+pub(crate) fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+/// Decodes a hex-prefix encoded path, returning its nibbles and whether it belongs to a leaf node.
+fn decode_compact(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let first = *encoded.first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode);
+    }
+
+    let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+    // An odd path length stores its first nibble next to the flag.
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(&encoded[1..]));
+
+    Ok((nibbles, flag & 2 == 2))
+}
+
+/// Verifies a Merkle-Patricia proof for `key` against the trie `root`.
+///
+/// The proof is the list of RLP-encoded nodes along the path from the root to the key, in the
+/// same order as returned by `eth_getProof`. Each node is checked against the hash committed to by
+/// its parent (or the root for the first node), and nodes embedded in their parent are followed
+/// without consuming a proof element.
+///
+/// # Arguments
+///
+/// - `root`: The root hash of the trie the proof is checked against.
+/// - `key`: The full trie key. For the state and storage tries this is already the Keccak256 hash
+///   of the address or slot.
+/// - `proof`: The RLP-encoded trie nodes along the path.
+///
+/// # Returns
+///
+/// - `Ok(Some(value))` with the raw value stored under `key` if the proof shows its inclusion.
+/// - `Ok(None)` if the proof shows that `key` is not present in the trie.
+/// - `Err(ProofError)` if the proof is incomplete or does not match `root`.
+pub fn verify_proof(
+    root: H256,
+    key: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, ProofError> {
+    if proof.is_empty() && root == EMPTY_TRIE_ROOT {
+        return Ok(None);
+    }
+
+    let nibbles = to_nibbles(key);
+    let mut path = nibbles.as_slice();
+    let mut next = NodeRef::Hash(root);
+    let mut nodes = proof.iter().enumerate();
+
+    loop {
+        let node = match next {
+            NodeRef::Hash(expected) => {
+                let (index, node) = nodes.next().ok_or(ProofError::MissingNode)?;
+                if keccak256(node) != expected {
+                    return Err(ProofError::InvalidNodeHash { index });
+                }
+                node.clone()
+            }
+            NodeRef::Inline(node) => node,
+        };
+
+        let rlp = Rlp::new(&node);
+        match rlp.item_count()? {
+            // Branch node: 16 children followed by the value stored at this path.
+            17 => {
+                let Some((&nibble, rest)) = path.split_first() else {
+                    let value = rlp.at(16)?.data()?.to_vec();
+                    return Ok((!value.is_empty()).then_some(value));
+                };
+                path = rest;
+                match NodeRef::from_child(&rlp.at(nibble as usize)?)? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+            }
+            // Extension or leaf node: a shared path segment followed by a child or a value.
+            2 => {
+                let (segment, is_leaf) = decode_compact(rlp.at(0)?.data()?)?;
+                if is_leaf {
+                    if path != segment.as_slice() {
+                        return Ok(None);
+                    }
+                    return Ok(Some(rlp.at(1)?.data()?.to_vec()));
+                }
+                let Some(rest) = path.strip_prefix(segment.as_slice()) else {
+                    return Ok(None);
+                };
+                path = rest;
+                match NodeRef::from_child(&rlp.at(1)?)? {
+                    Some(child) => next = child,
+                    None => return Err(ProofError::InvalidNode),
+                }
+            }
+            _ => return Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlp::RlpStream;
+
+    #[test]
+    fn test_empty_trie_root() {
+        assert_eq!(keccak256(&rlp::encode(&"")), EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn test_decode_compact() {
+        assert_eq!(decode_compact(&[0x20, 0x0f]).unwrap(), (vec![0, 15], true));
+        assert_eq!(
+            decode_compact(&[0x1a, 0xbc]).unwrap(),
+            (vec![10, 11, 12], false)
+        );
+        assert!(decode_compact(&[0x40]).is_err());
+    }
+
+    #[test]
+    fn test_single_leaf_inclusion_and_exclusion() {
+        let key = [0xab; 32];
+        let mut encoded_path = vec![0x20];
+        encoded_path.extend_from_slice(&key);
+
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&encoded_path);
+        stream.append(&b"value".to_vec());
+        let leaf = stream.out().to_vec();
+        let root = keccak256(&leaf);
+        let proof = vec![leaf];
+
+        assert_eq!(
+            verify_proof(root, &key, &proof).unwrap(),
+            Some(b"value".to_vec())
+        );
+        assert_eq!(verify_proof(root, &[0xcd; 32], &proof).unwrap(), None);
+        assert!(matches!(
+            verify_proof(H256::zero(), &key, &proof),
+            Err(ProofError::InvalidNodeHash { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_branch_and_extension_nodes() {
+        // The "do, dog, doge, horse" trie of the Ethereum trie tests, with the proof of "dog": an
+        // extension, a branch, an extension and a branch holding "do" and embedding "doge".
+        let root = H256::from_slice(
+            &hex::decode("5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")
+                .unwrap(),
+        );
+        let proof: Vec<Vec<u8>> = [
+            "e216a0bd3ee507e6c67cfefca98f84be47c1bbc009315fabc4405db4ba32190374572a",
+            "f84080808080a094a9f95bd89698e4da1812e0518053813b4d5b87caaf6b3c6fa57e9e50c0ff688080\
+             80cf85206f727365887374616c6c696f6e8080808080808080",
+            "e482006fa0d43b87fdcd4217013ccc92d04662e12d36e4cc25dc690077cd821a1956fc3e36",
+            "f3808080808080de17dc808080808080c63584636f696e8080808080808080808570757070798080808080\
+             808080808476657262",
+        ]
+        .iter()
+        .map(|node| hex::decode(node).unwrap())
+        .collect();
+
+        for (key, value) in [
+            ("dog", "puppy"),
+            ("do", "verb"),
+            ("doge", "coin"),
+            ("horse", "stallion"),
+        ] {
+            assert_eq!(
+                verify_proof(root, key.as_bytes(), &proof).unwrap(),
+                Some(value.as_bytes().to_vec())
+            );
+        }
+        for key in ["d", "cat", "dot", "doges", "horses"] {
+            assert_eq!(verify_proof(root, key.as_bytes(), &proof).unwrap(), None);
+        }
+        assert!(matches!(
+            verify_proof(root, b"dog", &proof[..3]),
+            Err(ProofError::MissingNode)
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+use super::mpt::{keccak256, to_nibbles, EMPTY_TRIE_ROOT};
+use primitive_types::H256;
+use rlp::RlpStream;
+
+/// Encodes a path segment using hex-prefix encoding, flagging whether it belongs to a leaf node.
+fn encode_compact(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(((flag | 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag << 4);
+        nibbles
+    };
+    encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// Appends a reference to a child node: its hash, or the node itself if it is shorter than 32 bytes.
+fn append_child(stream: &mut RlpStream, child: &[u8]) {
+    if child.len() < 32 {
+        stream.append_raw(child, 1);
+    } else {
+        stream.append(&keccak256(child));
+    }
+}
+
+/// An in-memory Merkle-Patricia trie built from a complete set of key/value pairs.
+///
+/// The trie is used to compute roots and generate proofs for lists whose full contents are known,
+/// such as the transactions and receipts of a block. Keys are kept as nibble paths, sorted so that
+/// each subtree is a contiguous range of entries.
+struct Trie {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Trie {
+    fn new(entries: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) -> Self {
+        let mut entries: Vec<_> = entries
+            .into_iter()
+            .map(|(key, value)| (to_nibbles(&key), value))
+            .collect();
+        entries.sort();
+        Trie { entries }
+    }
+
+    /// Encodes the node covering `entries`, whose paths share their first `depth` nibbles.
+    ///
+    /// Nodes lying on the path to `target` are pushed to `proof` from the deepest one upwards.
+    fn encode_node(
+        entries: &[(Vec<u8>, Vec<u8>)],
+        depth: usize,
+        target: Option<&[u8]>,
+        proof: &mut Vec<Vec<u8>>,
+    ) -> Vec<u8> {
+        let on_path = |path: &[u8]| target.is_some_and(|target| target.starts_with(path));
+
+        let node = if let [(path, value)] = entries {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&encode_compact(&path[depth..], true));
+            stream.append(value);
+            stream.out().to_vec()
+        } else {
+            let first = &entries[0].0;
+            let last = &entries[entries.len() - 1].0;
+            let shared = first[depth..]
+                .iter()
+                .zip(&last[depth..])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if shared > 0 {
+                let target = target.filter(|_| on_path(&first[..depth + shared]));
+                let child = Self::encode_node(entries, depth + shared, target, proof);
+                let mut stream = RlpStream::new_list(2);
+                stream.append(&encode_compact(&first[depth..depth + shared], false));
+                append_child(&mut stream, &child);
+                stream.out().to_vec()
+            } else {
+                let mut stream = RlpStream::new_list(17);
+                let mut value: &[u8] = &[];
+                let mut rest = entries;
+                if rest[0].0.len() == depth {
+                    value = &rest[0].1;
+                    rest = &rest[1..];
+                }
+                for nibble in 0..16u8 {
+                    let count = rest
+                        .iter()
+                        .take_while(|(path, _)| path[depth] == nibble)
+                        .count();
+                    let (children, remaining) = rest.split_at(count);
+                    rest = remaining;
+                    if children.is_empty() {
+                        stream.append_empty_data();
+                        continue;
+                    }
+                    let target = target.filter(|target| target.get(depth) == Some(&nibble));
+                    let child = Self::encode_node(children, depth + 1, target, proof);
+                    append_child(&mut stream, &child);
+                }
+                stream.append(&value);
+                stream.out().to_vec()
+            }
+        };
+
+        // Nodes embedded in their parent are not part of the proof, except for the root.
+        if target.is_some() && (node.len() >= 32 || depth == 0) {
+            proof.push(node.clone());
+        }
+        node
+    }
+
+    fn root_and_proof(&self, key: Option<&[u8]>) -> (H256, Vec<Vec<u8>>) {
+        if self.entries.is_empty() {
+            return (EMPTY_TRIE_ROOT, vec![]);
+        }
+
+        let target = key.map(to_nibbles);
+        let mut proof = vec![];
+        let root = Self::encode_node(&self.entries, 0, target.as_deref(), &mut proof);
+        proof.reverse();
+        (keccak256(&root), proof)
+    }
+}
+
+/// Builds the trie of an ordered list, keyed by the RLP encoding of each item's index.
+///
+/// This is the layout used for the `transactions_root`, `receipts_root` and `withdrawals_root`
+/// header fields.
+fn ordered_trie(items: &[Vec<u8>]) -> Trie {
+    Trie::new(
+        items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (rlp::encode(&index).to_vec(), item.clone())),
+    )
+}
+
+/// Computes the root of an ordered trie holding `items`, keyed by their RLP-encoded index.
+///
+/// # Arguments
+///
+/// - `items`: The encoded items (e.g. transactions or receipts) in block order.
+///
+/// # Returns
+///
+/// The `H256` root of the trie, or `EMPTY_TRIE_ROOT` for an empty list.
+pub fn ordered_trie_root(items: &[Vec<u8>]) -> H256 {
+    ordered_trie(items).root_and_proof(None).0
+}
+
+/// Computes the root of an ordered trie holding `items` and the proof for the item at `index`.
+///
+/// The proof lists the RLP-encoded trie nodes from the root to the item, and can be checked with
+/// `verify_proof` using the RLP-encoded index as the key.
+///
+/// # Arguments
+///
+/// - `items`: The encoded items (e.g. transactions or receipts) in block order.
+/// - `index`: The position of the item to prove.
+///
+/// # Returns
+///
+/// A tuple of the trie root and the proof nodes.
+pub fn ordered_trie_proof(items: &[Vec<u8>], index: usize) -> (H256, Vec<Vec<u8>>) {
+    ordered_trie(items).root_and_proof(Some(&rlp::encode(&index)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mpt::verify_proof;
+    use super::*;
+
+    #[test]
+    fn test_encode_compact() {
+        assert_eq!(encode_compact(&[0, 15], true), vec![0x20, 0x0f]);
+        assert_eq!(encode_compact(&[10, 11, 12], false), vec![0x1a, 0xbc]);
+    }
+
+    #[test]
+    fn test_ordered_trie_proofs() {
+        assert_eq!(ordered_trie_root(&[]), EMPTY_TRIE_ROOT);
+
+        // Mix small items, which get embedded in their parents, with large ones.
+        let items: Vec<Vec<u8>> = (0..300u32)
+            .map(|i| vec![i as u8; if i % 3 == 0 { 2 } else { 40 }])
+            .collect();
+
+        for index in [0, 1, 2, 127, 128, 255, 299] {
+            let (root, proof) = ordered_trie_proof(&items, index);
+            assert_eq!(root, ordered_trie_root(&items));
+
+            let key = rlp::encode(&index);
+            assert_eq!(
+                verify_proof(root, &key, &proof).unwrap(),
+                Some(items[index].clone())
+            );
+        }
+
+        let (root, proof) = ordered_trie_proof(&items, 300);
+        assert_eq!(
+            verify_proof(root, &rlp::encode(&300usize), &proof).unwrap(),
+            None
+        );
+    }
+}
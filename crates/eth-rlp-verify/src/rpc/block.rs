@@ -0,0 +1,419 @@
+use super::RpcError;
+use crate::eras::{
+    BlockHeaderDencun, BlockHeaderGenesis, BlockHeaderLondon, BlockHeaderParis, BlockHeaderPrague,
+    BlockHeaderShapella,
+};
+use crate::payload::{decode_hex, parse_h160, parse_h256, parse_quantity};
+use eth_rlp_types::{BlockHeader as VerifiableBlockHeader, BlockHeaderTrait};
+use primitive_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+/// A block object as returned by `eth_getBlockByNumber` and `eth_getBlockByHash`.
+///
+/// Only the header fields are modeled; transactions, withdrawals and other body fields are
+/// ignored when deserializing. The optional fields appear with the upgrades that introduced
+/// them: `baseFeePerGas` in London, `withdrawalsRoot` in Shapella, the blob gas fields and
+/// `parentBeaconBlockRoot` in Dencun, and `requestsHash` in Prague.
+///
+/// # Fields
+///
+/// All values are kept as the hex strings found in JSON-RPC responses.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBlock {
+    pub hash: String,
+    pub parent_hash: String,
+    pub sha3_uncles: String,
+    pub miner: String,
+    pub state_root: String,
+    pub transactions_root: String,
+    pub receipts_root: String,
+    pub logs_bloom: String,
+    pub difficulty: String,
+    pub number: String,
+    pub gas_limit: String,
+    pub gas_used: String,
+    pub timestamp: String,
+    pub extra_data: String,
+    pub mix_hash: String,
+    pub nonce: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_difficulty: Option<String>,
+}
+
+/// The header fields of an `RpcBlock`, parsed into their typed values.
+struct ParsedBlock {
+    parent_hash: H256,
+    ommers_hash: H256,
+    beneficiary: H160,
+    state_root: H256,
+    transactions_root: H256,
+    receipts_root: H256,
+    logs_bloom: [u8; 256],
+    difficulty: U256,
+    number: U256,
+    gas_limit: U256,
+    gas_used: U256,
+    timestamp: U256,
+    extra_data: Vec<u8>,
+    mix_hash: H256,
+    nonce: [u8; 8],
+}
+
+fn required<'a>(value: &'a Option<String>, field: &'static str) -> Result<&'a str, RpcError> {
+    value.as_deref().ok_or(RpcError::MissingField(field))
+}
+
+impl RpcBlock {
+    fn parse(&self) -> Result<ParsedBlock, RpcError> {
+        Ok(ParsedBlock {
+            parent_hash: parse_h256(&self.parent_hash)?,
+            ommers_hash: parse_h256(&self.sha3_uncles)?,
+            beneficiary: parse_h160(&self.miner)?,
+            state_root: parse_h256(&self.state_root)?,
+            transactions_root: parse_h256(&self.transactions_root)?,
+            receipts_root: parse_h256(&self.receipts_root)?,
+            logs_bloom: <BlockHeaderGenesis as BlockHeaderTrait>::hex_to_fixed_array::<256>(
+                &self.logs_bloom,
+            )?,
+            difficulty: parse_quantity(&self.difficulty)?,
+            number: parse_quantity(&self.number)?,
+            gas_limit: parse_quantity(&self.gas_limit)?,
+            gas_used: parse_quantity(&self.gas_used)?,
+            timestamp: parse_quantity(&self.timestamp)?,
+            extra_data: decode_hex(&self.extra_data)?,
+            mix_hash: parse_h256(&self.mix_hash)?,
+            nonce: <BlockHeaderGenesis as BlockHeaderTrait>::hex_to_fixed_array::<8>(&self.nonce)?,
+        })
+    }
+
+    fn base_fee_per_gas(&self) -> Result<U256, RpcError> {
+        self.quantity_field(&self.base_fee_per_gas, "baseFeePerGas")
+    }
+
+    /// Parses an optional quantity field, failing with `MissingField(field)` if it is absent.
+    fn quantity_field(
+        &self,
+        value: &Option<String>,
+        field: &'static str,
+    ) -> Result<U256, RpcError> {
+        Ok(parse_quantity(required(value, field)?)?)
+    }
+
+    /// Parses an optional hash field, failing with `MissingField(field)` if it is absent.
+    fn h256_field(&self, value: &Option<String>, field: &'static str) -> Result<H256, RpcError> {
+        Ok(parse_h256(required(value, field)?)?)
+    }
+
+    /// Returns the block number.
+    pub fn block_number(&self) -> Result<u64, RpcError> {
+        let number = parse_quantity(&self.number)?;
+        u64::try_from(number).map_err(|_| RpcError::Overflow("number"))
+    }
+
+    /// Computes the hash of the block header.
+    ///
+    /// The header type is picked from the fields present in the response rather than from the
+    /// block number, so that the newest field present determines the era.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<H256, RpcError>` containing the computed block hash.
+    pub fn compute_hash(&self) -> Result<H256, RpcError> {
+        if self.requests_hash.is_some() {
+            Ok(BlockHeaderPrague::try_from(self)?.compute_hash())
+        } else if self.parent_beacon_block_root.is_some() {
+            Ok(BlockHeaderDencun::try_from(self)?.compute_hash())
+        } else if self.withdrawals_root.is_some() {
+            Ok(BlockHeaderShapella::try_from(self)?.compute_hash())
+        } else if self.base_fee_per_gas.is_some() {
+            Ok(BlockHeaderLondon::try_from(self)?.compute_hash())
+        } else {
+            Ok(BlockHeaderGenesis::try_from(self)?.compute_hash())
+        }
+    }
+
+    /// Verifies the `hash` returned by the node against the hash of the block header.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<bool, RpcError>` indicating whether the returned hash is correct.
+    pub fn verify_hash(&self) -> Result<bool, RpcError> {
+        Ok(self.compute_hash()? == parse_h256(&self.hash)?)
+    }
+}
+
+/// Implements `TryFrom<&RpcBlock>` for an era header.
+///
+/// The fields shared by every era are taken from `RpcBlock::parse`. The fields introduced by later
+/// upgrades are listed as `field: value` after `|block|`, which binds the `RpcBlock` for the values.
+macro_rules! impl_try_from_rpc_block {
+    ($header:ident) => {
+        impl_try_from_rpc_block!($header, |_block| {});
+    };
+    ($header:ident, |$block:ident| { $($field:ident: $value:expr),* $(,)? }) => {
+        impl TryFrom<&RpcBlock> for $header {
+            type Error = RpcError;
+
+            fn try_from(block: &RpcBlock) -> Result<Self, Self::Error> {
+                let parsed = block.parse()?;
+                let $block = block;
+                Ok($header {
+                    parent_hash: parsed.parent_hash,
+                    ommers_hash: parsed.ommers_hash,
+                    beneficiary: parsed.beneficiary,
+                    state_root: parsed.state_root,
+                    transactions_root: parsed.transactions_root,
+                    receipts_root: parsed.receipts_root,
+                    logs_bloom: parsed.logs_bloom,
+                    difficulty: parsed.difficulty,
+                    number: parsed.number,
+                    gas_limit: parsed.gas_limit,
+                    gas_used: parsed.gas_used,
+                    timestamp: parsed.timestamp,
+                    extra_data: parsed.extra_data,
+                    mix_hash: parsed.mix_hash,
+                    nonce: parsed.nonce,
+                    $($field: $value,)*
+                })
+            }
+        }
+    };
+}
+
+impl_try_from_rpc_block!(BlockHeaderGenesis);
+impl_try_from_rpc_block!(BlockHeaderLondon, |block| {
+    base_fee_per_gas: block.base_fee_per_gas()?,
+});
+impl_try_from_rpc_block!(BlockHeaderParis, |block| {
+    base_fee_per_gas: block.base_fee_per_gas()?,
+});
+impl_try_from_rpc_block!(BlockHeaderShapella, |block| {
+    base_fee_per_gas: block.base_fee_per_gas()?,
+    withdrawals_root: block.h256_field(&block.withdrawals_root, "withdrawalsRoot")?,
+});
+impl_try_from_rpc_block!(BlockHeaderDencun, |block| {
+    base_fee_per_gas: block.base_fee_per_gas()?,
+    withdrawals_root: block.h256_field(&block.withdrawals_root, "withdrawalsRoot")?,
+    parent_beacon_block_root: block
+        .h256_field(&block.parent_beacon_block_root, "parentBeaconBlockRoot")?,
+    blob_gas_used: block.quantity_field(&block.blob_gas_used, "blobGasUsed")?,
+    excess_blob_gas: block.quantity_field(&block.excess_blob_gas, "excessBlobGas")?,
+});
+impl_try_from_rpc_block!(BlockHeaderPrague, |block| {
+    base_fee_per_gas: block.base_fee_per_gas()?,
+    withdrawals_root: block.h256_field(&block.withdrawals_root, "withdrawalsRoot")?,
+    parent_beacon_block_root: block
+        .h256_field(&block.parent_beacon_block_root, "parentBeaconBlockRoot")?,
+    blob_gas_used: block.quantity_field(&block.blob_gas_used, "blobGasUsed")?,
+    excess_blob_gas: block.quantity_field(&block.excess_blob_gas, "excessBlobGas")?,
+    requests_hash: block.h256_field(&block.requests_hash, "requestsHash")?,
+});
+
+impl TryFrom<&RpcBlock> for VerifiableBlockHeader {
+    type Error = RpcError;
+
+    /// Converts an RPC block into the database `BlockHeader`, so that it can be checked with
+    /// `verify_block` or stored.
+    fn try_from(block: &RpcBlock) -> Result<Self, Self::Error> {
+        let as_i64 = |value: &str, field: &'static str| -> Result<i64, RpcError> {
+            i64::try_from(parse_quantity(value)?).map_err(|_| RpcError::Overflow(field))
+        };
+
+        Ok(VerifiableBlockHeader {
+            block_hash: block.hash.clone(),
+            number: as_i64(&block.number, "number")?,
+            gas_limit: as_i64(&block.gas_limit, "gasLimit")?,
+            gas_used: as_i64(&block.gas_used, "gasUsed")?,
+            nonce: block.nonce.clone(),
+            transaction_root: Some(block.transactions_root.clone()),
+            receipts_root: Some(block.receipts_root.clone()),
+            state_root: Some(block.state_root.clone()),
+            base_fee_per_gas: block.base_fee_per_gas.clone(),
+            parent_hash: Some(block.parent_hash.clone()),
+            ommers_hash: Some(block.sha3_uncles.clone()),
+            miner: Some(block.miner.clone()),
+            logs_bloom: Some(block.logs_bloom.clone()),
+            difficulty: Some(block.difficulty.clone()),
+            totaldifficulty: block.total_difficulty.clone(),
+            sha3_uncles: Some(block.sha3_uncles.clone()),
+            timestamp: Some(block.timestamp.clone()),
+            extra_data: Some(block.extra_data.clone()),
+            mix_hash: Some(block.mix_hash.clone()),
+            withdrawals_root: block.withdrawals_root.clone(),
+            blob_gas_used: block.blob_gas_used.clone(),
+            excess_blob_gas: block.excess_blob_gas.clone(),
+            parent_beacon_block_root: block.parent_beacon_block_root.clone(),
+            requests_hash: block.requests_hash.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{DENCUN_START, LONDON_START, PARIS_START, PRAGUE_START, SHAPELLA_START};
+    use crate::proofs::keccak256;
+    use crate::test_helpers::{
+        create_test_block_header_london, create_test_block_header_paris,
+        create_test_block_header_shapella,
+    };
+    use crate::{encode_block_header, verify_block};
+
+    /// The mainnet genesis block, as returned by `eth_getBlockByNumber("0x0", false)`.
+    const GENESIS_BLOCK: &str = r#"{
+        "difficulty": "0x400000000",
+        "extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+        "gasLimit": "0x1388",
+        "gasUsed": "0x0",
+        "hash": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+        "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        "miner": "0x0000000000000000000000000000000000000000",
+        "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "nonce": "0x0000000000000042",
+        "number": "0x0",
+        "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "receiptsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+        "size": "0x21c",
+        "stateRoot": "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+        "timestamp": "0x0",
+        "totalDifficulty": "0x400000000",
+        "transactions": [],
+        "transactionsRoot": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+        "uncles": []
+    }"#;
+
+    #[test]
+    fn test_rpc_block_hash() {
+        let block: RpcBlock = serde_json::from_str(GENESIS_BLOCK).unwrap();
+        assert_eq!(block.block_number().unwrap(), 0);
+        assert!(block.verify_hash().unwrap());
+
+        let header = VerifiableBlockHeader::try_from(&block).unwrap();
+        assert!(verify_block(0, header, &block.hash).unwrap());
+
+        let tampered = RpcBlock {
+            gas_limit: "0x1389".to_string(),
+            ..block
+        };
+        assert!(!tampered.verify_hash().unwrap());
+    }
+
+    #[test]
+    fn test_rpc_block_missing_fields() {
+        let block: RpcBlock = serde_json::from_str(GENESIS_BLOCK).unwrap();
+        assert!(matches!(
+            BlockHeaderLondon::try_from(&block),
+            Err(RpcError::MissingField("baseFeePerGas"))
+        ));
+
+        let block = RpcBlock {
+            base_fee_per_gas: Some("0x7".to_string()),
+            withdrawals_root: Some(format!("{:?}", H256::zero())),
+            parent_beacon_block_root: Some(format!("{:?}", H256::zero())),
+            requests_hash: Some(format!("{:?}", H256::zero())),
+            ..block
+        };
+        assert!(matches!(
+            block.compute_hash(),
+            Err(RpcError::MissingField("blobGasUsed"))
+        ));
+    }
+
+    /// Builds the `eth_getBlockByNumber` response a node would return for `header`.
+    fn rpc_block(header: &VerifiableBlockHeader) -> RpcBlock {
+        let mut block = serde_json::json!({
+            "hash": header.block_hash,
+            "parentHash": header.parent_hash,
+            "sha3Uncles": header.ommers_hash,
+            "miner": header.miner,
+            "stateRoot": header.state_root,
+            "transactionsRoot": header.transaction_root,
+            "receiptsRoot": header.receipts_root,
+            "logsBloom": header.logs_bloom,
+            "difficulty": header.difficulty,
+            "number": format!("{:#x}", header.number),
+            "gasLimit": format!("{:#x}", header.gas_limit),
+            "gasUsed": format!("{:#x}", header.gas_used),
+            "timestamp": header.timestamp,
+            "extraData": header.extra_data,
+            "mixHash": header.mix_hash,
+            "nonce": header.nonce,
+            "baseFeePerGas": header.base_fee_per_gas,
+            "withdrawalsRoot": header.withdrawals_root,
+            "blobGasUsed": header.blob_gas_used,
+            "excessBlobGas": header.excess_blob_gas,
+            "parentBeaconBlockRoot": header.parent_beacon_block_root,
+            "requestsHash": header.requests_hash,
+            "transactions": [],
+            "uncles": [],
+        });
+        block
+            .as_object_mut()
+            .unwrap()
+            .retain(|_, value| !value.is_null());
+        serde_json::from_value(block).unwrap()
+    }
+
+    #[test]
+    fn test_rpc_block_eras() {
+        // Each header is hashed through the database path of its era, which parses the header
+        // independently of `RpcBlock::parse`, and the response must give the same hash.
+        let london = VerifiableBlockHeader {
+            number: LONDON_START as i64,
+            ..create_test_block_header_london()
+        };
+        let paris = VerifiableBlockHeader {
+            number: PARIS_START as i64,
+            ..create_test_block_header_paris()
+        };
+        let shapella = VerifiableBlockHeader {
+            number: SHAPELLA_START as i64,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+            ..create_test_block_header_shapella()
+        };
+        let dencun = VerifiableBlockHeader {
+            number: DENCUN_START as i64,
+            ..create_test_block_header_shapella()
+        };
+        let prague = VerifiableBlockHeader {
+            number: PRAGUE_START as i64,
+            requests_hash: Some(format!("{:?}", H256::repeat_byte(0xe3))),
+            ..create_test_block_header_shapella()
+        };
+
+        for mut header in [london, paris, shapella, dencun, prague] {
+            let number = header.number as u64;
+            let encoded = encode_block_header(number, header.clone()).unwrap();
+            header.block_hash = format!("{:?}", keccak256(&encoded));
+
+            let block = rpc_block(&header);
+            assert_eq!(block.block_number().unwrap(), number);
+            assert!(block.verify_hash().unwrap(), "block {number}");
+
+            let converted = VerifiableBlockHeader::try_from(&block).unwrap();
+            assert!(verify_block(number, converted, &block.hash).unwrap());
+
+            let tampered = RpcBlock {
+                gas_used: "0x1".to_string(),
+                ..block
+            };
+            assert!(!tampered.verify_hash().unwrap());
+        }
+    }
+}
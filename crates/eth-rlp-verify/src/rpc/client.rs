@@ -0,0 +1,440 @@
+use super::{RpcBlock, RpcError};
+use crate::chain::{follows, parse_hash, BlockResult};
+use crate::verify_block;
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use serde::Deserialize;
+use serde_json::json;
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RpcClientError {
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] Box<ureq::Error>),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Node error {code}: {message}")]
+    NodeError { code: i64, message: String },
+    #[error("Node returned no block {number}")]
+    MissingBlock { number: u64 },
+    #[error("Block error: {0}")]
+    BlockError(#[from] RpcError),
+}
+
+/// The error object of a JSON-RPC response.
+#[derive(Debug, Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+/// The JSON-RPC error codes nodes and providers use for rate limiting, which are answered with an
+/// HTTP 200 and retried like an HTTP 429.
+const RETRYABLE_ERROR_CODES: [i64; 2] = [429, -32005];
+
+/// A JSON-RPC response to `eth_getBlockByNumber`.
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    result: Option<RpcBlock>,
+    #[serde(default)]
+    error: Option<ResponseError>,
+}
+
+/// The body of a response to a batch request. Nodes rejecting a whole batch, e.g. because it is
+/// too large, answer with a single error object instead of an array.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ResponseBody {
+    Batch(Vec<Response>),
+    Single(Box<Response>),
+}
+
+/// A blocking JSON-RPC client fetching blocks from an Ethereum node.
+///
+/// Blocks are fetched with batched `eth_getBlockByNumber` requests. Requests failing with a
+/// transport error, an HTTP 429 or a 5xx status, or answered with a rate limiting JSON-RPC error,
+/// are retried with a linearly increasing delay.
+///
+/// # Fields
+///
+/// - `url`: The URL of the node.
+/// - `agent`: The HTTP agent, reusing connections between requests.
+/// - `batch_size`: The number of blocks requested per HTTP request.
+/// - `max_retries`: The number of times a failed request is retried.
+/// - `retry_delay`: The delay before the first retry.
+#[derive(Clone, Debug)]
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+    batch_size: usize,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl RpcClient {
+    /// Creates a client requesting 50 blocks per batch and retrying failed requests 3 times.
+    pub fn new(url: &str) -> Self {
+        RpcClient {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            batch_size: 50,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+
+    /// Sets the number of blocks requested per HTTP request. Many providers cap batches at 100.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the number of retries of a failed request and the delay before the first one.
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Sends a request body, retrying on transient failures.
+    fn post(&self, body: &str) -> Result<String, RpcClientError> {
+        let mut attempt = 0;
+        loop {
+            let error = match self
+                .agent
+                .post(&self.url)
+                .set("Content-Type", "application/json")
+                .send_string(body)
+            {
+                Ok(response) => return Ok(response.into_string()?),
+                Err(error) => error,
+            };
+
+            let transient = match &error {
+                ureq::Error::Status(status, _) => *status == 429 || *status >= 500,
+                ureq::Error::Transport(_) => true,
+            };
+            if !transient || attempt >= self.max_retries {
+                return Err(Box::new(error).into());
+            }
+            attempt += 1;
+            thread::sleep(self.retry_delay * attempt);
+        }
+    }
+
+    /// Fetches a range of blocks, without their transactions.
+    ///
+    /// # Arguments
+    ///
+    /// - `range`: The block numbers to fetch.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<RpcBlock>, RpcClientError>` containing the blocks in ascending order.
+    pub fn fetch_blocks(
+        &self,
+        range: RangeInclusive<u64>,
+    ) -> Result<Vec<RpcBlock>, RpcClientError> {
+        let mut blocks = Vec::new();
+        for batch in self.batches(range) {
+            blocks.extend(self.fetch_batch(batch)?);
+        }
+        Ok(blocks)
+    }
+
+    /// Splits a range of block numbers into batches of at most `batch_size` blocks.
+    fn batches(&self, range: RangeInclusive<u64>) -> impl Iterator<Item = RangeInclusive<u64>> {
+        let (start, end) = range.into_inner();
+        let batch_size = self.batch_size as u64;
+        (start..=end)
+            .step_by(self.batch_size)
+            .map(move |first| first..=end.min(first.saturating_add(batch_size - 1)))
+    }
+
+    /// Fetches the blocks of one batch, using each block number as its request id.
+    fn fetch_batch(&self, numbers: RangeInclusive<u64>) -> Result<Vec<RpcBlock>, RpcClientError> {
+        let requests: Vec<_> = numbers
+            .clone()
+            .map(|number| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": number,
+                    "method": "eth_getBlockByNumber",
+                    "params": [format!("{number:#x}"), false],
+                })
+            })
+            .collect();
+        let body = serde_json::to_string(&requests)?;
+
+        let mut attempt = 0;
+        let mut responses = loop {
+            let mut responses = match serde_json::from_str(&self.post(&body)?)? {
+                ResponseBody::Batch(responses) => responses,
+                ResponseBody::Single(response) => vec![*response],
+            };
+            let error = match responses
+                .iter_mut()
+                .find_map(|response| response.error.take())
+            {
+                Some(error) => error,
+                None => break responses,
+            };
+            if !RETRYABLE_ERROR_CODES.contains(&error.code) || attempt >= self.max_retries {
+                return Err(RpcClientError::NodeError {
+                    code: error.code,
+                    message: error.message,
+                });
+            }
+            attempt += 1;
+            thread::sleep(self.retry_delay * attempt);
+        };
+
+        numbers
+            .map(|number| {
+                responses
+                    .iter_mut()
+                    .find(|response| response.id == Some(number))
+                    .and_then(|response| response.result.take())
+                    .ok_or(RpcClientError::MissingBlock { number })
+            })
+            .collect()
+    }
+
+    /// Fetches and verifies a range of blocks.
+    ///
+    /// Each block is converted into a `VerifiableBlockHeader` and checked with `verify_block`.
+    /// Every block must also link to the previous one.
+    ///
+    /// # Arguments
+    ///
+    /// - `range`: The block numbers to verify.
+    ///
+    /// # Returns
+    ///
+    /// A `Result<Vec<BlockResult>, RpcClientError>` with the result of every block, or an error if
+    /// a block could not be fetched or converted.
+    pub fn verify_range(
+        &self,
+        range: RangeInclusive<u64>,
+    ) -> Result<Vec<BlockResult>, RpcClientError> {
+        let mut results = Vec::new();
+        let mut previous = None;
+        for batch in self.batches(range) {
+            for block in self.fetch_batch(batch)? {
+                let number = block.block_number()?;
+                let hash = parse_hash(&block.hash).ok();
+                let parent_hash = parse_hash(&block.parent_hash).ok();
+
+                let block_header = VerifiableBlockHeader::try_from(&block)?;
+                let hash_valid =
+                    verify_block(number, block_header, &block.hash).unwrap_or_default();
+                let linked = previous.is_none_or(|previous| follows(previous, number, parent_hash));
+
+                previous = Some((number, hash));
+                results.push(BlockResult {
+                    number,
+                    block_hash: block.hash,
+                    hash_valid,
+                    linked,
+                });
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_chain;
+    use serde_json::Value;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn to_rpc_block(header: &VerifiableBlockHeader) -> RpcBlock {
+        RpcBlock {
+            hash: header.block_hash.clone(),
+            parent_hash: header.parent_hash.clone().unwrap_or_default(),
+            sha3_uncles: header.sha3_uncles.clone().unwrap_or_default(),
+            miner: header.miner.clone().unwrap_or_default(),
+            state_root: header.state_root.clone().unwrap_or_default(),
+            transactions_root: header.transaction_root.clone().unwrap_or_default(),
+            receipts_root: header.receipts_root.clone().unwrap_or_default(),
+            logs_bloom: header.logs_bloom.clone().unwrap_or_default(),
+            difficulty: header.difficulty.clone().unwrap_or_default(),
+            number: format!("{:#x}", header.number),
+            gas_limit: format!("{:#x}", header.gas_limit),
+            gas_used: format!("{:#x}", header.gas_used),
+            timestamp: header.timestamp.clone().unwrap_or_default(),
+            extra_data: header.extra_data.clone().unwrap_or_default(),
+            mix_hash: header.mix_hash.clone().unwrap_or_default(),
+            nonce: header.nonce.clone(),
+            base_fee_per_gas: None,
+            withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+            requests_hash: None,
+            total_difficulty: None,
+        }
+    }
+
+    /// How the test server answers the requests it fails.
+    #[derive(Clone, Copy)]
+    enum Failure {
+        /// An HTTP 503 status.
+        Unavailable,
+        /// An HTTP 200 carrying a JSON-RPC error with the given code.
+        NodeError(i64),
+    }
+
+    /// Serves `eth_getBlockByNumber` batches for `blocks`, answering the first `failures` requests
+    /// with `failure`.
+    ///
+    /// # Returns
+    ///
+    /// The URL of the server and the number of requests it received.
+    fn serve(
+        blocks: Vec<RpcBlock>,
+        failures: usize,
+        failure: Failure,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let failed = counter.fetch_add(1, Ordering::SeqCst) < failures;
+                let (status, body) = match failure {
+                    Failure::Unavailable if failed => ("503 Service Unavailable", String::new()),
+                    Failure::NodeError(code) if failed => {
+                        let error = json!({
+                            "jsonrpc": "2.0",
+                            "id": null,
+                            "error": { "code": code, "message": "request failed" },
+                        });
+                        ("200 OK", error.to_string())
+                    }
+                    _ => {
+                        let batch: Vec<Value> = serde_json::from_slice(&body).unwrap();
+                        let responses: Vec<Value> = batch
+                            .iter()
+                            .rev()
+                            .map(|request| {
+                                let number = request["params"][0].as_str().unwrap().to_string();
+                                let block = blocks.iter().find(|block| block.number == number);
+                                json!({ "jsonrpc": "2.0", "id": request["id"], "result": block })
+                            })
+                            .collect();
+                        ("200 OK", serde_json::to_string(&responses).unwrap())
+                    }
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn chain() -> Vec<RpcBlock> {
+        create_test_chain(0, 10).iter().map(to_rpc_block).collect()
+    }
+
+    #[test]
+    fn test_verify_range_in_batches() {
+        let (url, requests) = serve(chain(), 2, Failure::Unavailable);
+        let client = RpcClient::new(&url)
+            .with_batch_size(4)
+            .with_retries(2, Duration::from_millis(1));
+
+        let results = client.verify_range(0..=9).unwrap();
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(BlockResult::is_valid));
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_verify_range_failures() {
+        let mut blocks = chain();
+        blocks[3].gas_used = "0x1".to_string();
+        blocks.remove(6);
+        blocks[6].number = "0x6".to_string();
+        let (url, _) = serve(blocks, 0, Failure::Unavailable);
+        let client = RpcClient::new(&url).with_batch_size(3);
+
+        let results = client.verify_range(0..=6).unwrap();
+        let invalid: Vec<u64> = results
+            .iter()
+            .filter(|result| !result.is_valid())
+            .map(|result| result.number)
+            .collect();
+        assert_eq!(invalid, vec![3, 6]);
+
+        assert!(matches!(
+            client.fetch_blocks(8..=10),
+            Err(RpcClientError::MissingBlock { number: 10 })
+        ));
+    }
+
+    #[test]
+    fn test_retries_exhausted() {
+        let (url, requests) = serve(chain(), 3, Failure::Unavailable);
+        let client = RpcClient::new(&url).with_retries(1, Duration::from_millis(1));
+        assert!(matches!(
+            client.fetch_blocks(0..=1),
+            Err(RpcClientError::HttpError(_))
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retry_rate_limited_node_errors() {
+        let (url, requests) = serve(chain(), 2, Failure::NodeError(-32005));
+        let client = RpcClient::new(&url).with_retries(2, Duration::from_millis(1));
+        assert_eq!(client.fetch_blocks(0..=1).unwrap().len(), 2);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let (url, requests) = serve(chain(), 1, Failure::NodeError(-32601));
+        let client = RpcClient::new(&url).with_retries(2, Duration::from_millis(1));
+        assert!(matches!(
+            client.fetch_blocks(0..=1),
+            Err(RpcClientError::NodeError { code: -32601, .. })
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
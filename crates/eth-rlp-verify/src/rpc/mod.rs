@@ -0,0 +1,22 @@
+mod block;
+#[cfg(feature = "rpc")]
+mod client;
+
+use eth_rlp_types::BlockHeaderError;
+use thiserror::Error;
+
+pub use block::RpcBlock;
+#[cfg(feature = "rpc")]
+pub use client::{RpcClient, RpcClientError};
+
+#[derive(Error, Debug)]
+pub enum RpcError {
+    #[error("Block has no {0}")]
+    MissingField(&'static str),
+    #[error("Block {0} does not fit in 64 bits")]
+    Overflow(&'static str),
+    #[error("Hex decoding error: {0}")]
+    HexDecodingError(#[from] hex::FromHexError),
+    #[error("Block header error: {0}")]
+    BlockHeaderError(#[from] BlockHeaderError),
+}
@@ -0,0 +1,157 @@
+use primitive_types::{H256, U256};
+use sha2::{Digest, Sha256};
+
+/// Hashes two 32-byte chunks into their parent using SHA-256, as done by SSZ merkleization.
+pub fn hash_pair(left: &H256, right: &H256) -> H256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    H256::from_slice(&hasher.finalize())
+}
+
+/// Returns the root of a subtree of the given depth holding only zero chunks.
+pub fn zero_hash(depth: usize) -> H256 {
+    (0..depth).fold(H256::zero(), |hash, _| hash_pair(&hash, &hash))
+}
+
+/// Packs a `uint64` into a little-endian SSZ chunk.
+pub fn uint64_chunk(value: u64) -> H256 {
+    let mut chunk = [0u8; 32];
+    chunk[..8].copy_from_slice(&value.to_le_bytes());
+    H256(chunk)
+}
+
+/// Packs a `uint256` into a little-endian SSZ chunk.
+pub fn uint256_chunk(value: U256) -> H256 {
+    H256(value.to_little_endian())
+}
+
+/// Merkleizes `chunks` into a tree of the given depth, padding with zero chunks.
+///
+/// The depth is `log2` of the chunk limit of the SSZ type, so that lists are merkleized against
+/// their maximum capacity rather than their actual length.
+pub fn merkleize(chunks: &[H256], depth: usize) -> H256 {
+    let mut layer = chunks.to_vec();
+    for level in 0..depth {
+        if layer.is_empty() {
+            return zero_hash(depth);
+        }
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash(level));
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer.first().copied().unwrap_or_else(|| zero_hash(depth))
+}
+
+/// Returns the Merkle branch of the chunk at `index` in a tree of the given depth, bottom-up.
+pub fn merkle_branch(chunks: &[H256], depth: usize, index: usize) -> Vec<H256> {
+    let mut layer = chunks.to_vec();
+    let mut index = index;
+    let mut branch = Vec::with_capacity(depth);
+    for level in 0..depth {
+        branch.push(
+            layer
+                .get(index ^ 1)
+                .copied()
+                .unwrap_or_else(|| zero_hash(level)),
+        );
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash(level));
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+        index /= 2;
+    }
+    branch
+}
+
+/// Mixes the length of a list into the root of its merkleized contents.
+pub fn mix_in_length(root: H256, length: usize) -> H256 {
+    hash_pair(&root, &uint64_chunk(length as u64))
+}
+
+/// Checks a Merkle branch for `leaf` at `index` against `root`, as `is_valid_merkle_branch` does in
+/// the consensus specs.
+///
+/// # Arguments
+///
+/// - `leaf`: The chunk being proven.
+/// - `branch`: The sibling chunks, bottom-up. Only the first `depth` of them are used.
+/// - `depth`: The depth of the leaf below `root`.
+/// - `index`: The position of the leaf at that depth.
+/// - `root`: The expected root.
+///
+/// # Returns
+///
+/// A `bool` indicating whether the branch is valid.
+pub fn is_valid_merkle_branch(
+    leaf: H256,
+    branch: &[H256],
+    depth: usize,
+    index: u64,
+    root: H256,
+) -> bool {
+    if branch.len() < depth {
+        return false;
+    }
+
+    let computed = branch[..depth]
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 1 {
+                hash_pair(sibling, &node)
+            } else {
+                hash_pair(&node, sibling)
+            }
+        });
+    computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merkleize_and_branches() {
+        let chunks: Vec<H256> = (1..=5).map(H256::from_low_u64_be).collect();
+        let root = merkleize(&chunks, 4);
+
+        let expected = hash_pair(
+            &hash_pair(
+                &hash_pair(
+                    &hash_pair(&chunks[0], &chunks[1]),
+                    &hash_pair(&chunks[2], &chunks[3]),
+                ),
+                &hash_pair(&hash_pair(&chunks[4], &zero_hash(0)), &zero_hash(1)),
+            ),
+            &zero_hash(3),
+        );
+        assert_eq!(root, expected);
+        assert_eq!(merkleize(&[], 4), zero_hash(4));
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let branch = merkle_branch(&chunks, 4, index);
+            assert!(is_valid_merkle_branch(
+                *chunk,
+                &branch,
+                4,
+                index as u64,
+                root
+            ));
+            assert!(!is_valid_merkle_branch(
+                *chunk,
+                &branch,
+                4,
+                index as u64 ^ 1,
+                root
+            ));
+        }
+    }
+}
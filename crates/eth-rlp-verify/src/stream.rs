@@ -0,0 +1,133 @@
+use crate::chain::{BlockResult, ChainVerifier};
+use eth_rlp_types::BlockHeader as VerifiableBlockHeader;
+use futures_util::stream::{Stream, StreamExt, TryStreamExt};
+
+/// Verifies a stream of headers, in ascending block number order.
+///
+/// Headers go through a `ChainVerifier`, so that both hashes and parent links are checked. The
+/// returned stream polls the input only when it is polled itself, so a slow consumer slows the
+/// producer down instead of letting headers pile up in memory.
+///
+/// # Arguments
+///
+/// - `block_headers`: The headers to verify.
+///
+/// # Returns
+///
+/// A stream yielding the `BlockResult` of every header.
+pub fn verify_stream<S>(block_headers: S) -> impl Stream<Item = BlockResult>
+where
+    S: Stream<Item = VerifiableBlockHeader>,
+{
+    let mut verifier = ChainVerifier::new();
+    block_headers.map(move |block_header| verifier.push(block_header))
+}
+
+/// Verifies a fallible stream of headers, e.g. rows streamed from a database.
+///
+/// Errors are passed through unchanged, and the next header is checked against the last header
+/// received.
+///
+/// # Arguments
+///
+/// - `block_headers`: The headers to verify.
+///
+/// # Returns
+///
+/// A stream yielding the `BlockResult` of every header, or the errors of the input stream.
+pub fn try_verify_stream<S, E>(block_headers: S) -> impl Stream<Item = Result<BlockResult, E>>
+where
+    S: Stream<Item = Result<VerifiableBlockHeader, E>>,
+{
+    let mut verifier = ChainVerifier::new();
+    block_headers.map_ok(move |block_header| verifier.push(block_header))
+}
+
+/// Verifies a stream of headers in batches, e.g. to write results in bulk.
+///
+/// A batch holds the headers already available when it is polled, up to `batch_size`: the stream
+/// never waits for a full batch, so that results keep flowing when the input is slow.
+///
+/// # Arguments
+///
+/// - `block_headers`: The headers to verify.
+/// - `batch_size`: The maximum number of headers per batch.
+///
+/// # Returns
+///
+/// A stream yielding the results of every batch, in order.
+pub fn verify_stream_batches<S>(
+    block_headers: S,
+    batch_size: usize,
+) -> impl Stream<Item = Vec<BlockResult>>
+where
+    S: Stream<Item = VerifiableBlockHeader>,
+{
+    let mut verifier = ChainVerifier::new();
+    block_headers
+        .ready_chunks(batch_size.max(1))
+        .map(move |batch| verifier.verify_iter(batch).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::create_test_chain;
+    use futures_util::stream;
+    use futures_util::FutureExt;
+
+    fn chain() -> Vec<VerifiableBlockHeader> {
+        create_test_chain(0, 5)
+    }
+
+    #[test]
+    fn test_verify_stream() {
+        let mut chain = chain();
+        chain[3].gas_used += 1;
+
+        let results: Vec<BlockResult> = verify_stream(stream::iter(chain.clone()))
+            .collect()
+            .now_or_never()
+            .unwrap();
+        let mut verifier = ChainVerifier::new();
+        assert_eq!(
+            results,
+            verifier.verify_iter(chain.clone()).collect::<Vec<_>>()
+        );
+
+        let rows = chain
+            .into_iter()
+            .map(Ok)
+            .chain(std::iter::once(Err("connection lost")));
+        let results: Vec<Result<BlockResult, &str>> = try_verify_stream(stream::iter(rows))
+            .collect()
+            .now_or_never()
+            .unwrap();
+        let failures: Vec<Result<u64, &str>> = results
+            .into_iter()
+            .filter(|result| !result.as_ref().is_ok_and(BlockResult::is_valid))
+            .map(|result| result.map(|result| result.number))
+            .collect();
+        assert_eq!(failures, vec![Ok(3), Err("connection lost")]);
+    }
+
+    #[test]
+    fn test_verify_stream_batches() {
+        // The input stalls after the last header, like a connection waiting for new blocks.
+        let block_headers = stream::iter(chain()).chain(stream::pending());
+        let mut batches = Box::pin(verify_stream_batches(block_headers, 3));
+
+        let batch = batches.next().now_or_never().unwrap().unwrap();
+        assert_eq!(
+            batch.iter().map(|result| result.number).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        let batch = batches.next().now_or_never().unwrap().unwrap();
+        assert_eq!(
+            batch.iter().map(|result| result.number).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert!(batch.iter().all(BlockResult::is_valid));
+        assert!(batches.next().now_or_never().is_none());
+    }
+}
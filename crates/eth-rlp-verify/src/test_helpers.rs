@@ -1,4 +1,9 @@
-use eth_rlp_types::BlockHeader;
+use crate::constants::PRAGUE_START;
+use crate::encode_block_header;
+use crate::eras::BlockHeaderGenesis;
+use crate::proofs::keccak256;
+use eth_rlp_types::{BlockHeader, BlockHeaderTrait};
+use primitive_types::H256;
 
 pub fn create_test_block_header_shapella() -> BlockHeader {
     BlockHeader {
@@ -44,6 +49,7 @@ pub fn create_test_block_header_shapella() -> BlockHeader {
         parent_beacon_block_root: Some(
             "0x2b5b8c2d329148bc5d724cac0a7abc557f93471c24ea027b119ecedb937c0045".to_string(),
         ),
+        requests_hash: None,
     }
 }
 
@@ -90,6 +96,7 @@ pub fn create_test_block_header_paris() -> BlockHeader {
         blob_gas_used: None,
         excess_blob_gas: None,
         parent_beacon_block_root: None,
+        requests_hash: None,
     }
 }
 
@@ -118,5 +125,84 @@ pub fn create_test_block_header_london() -> BlockHeader {
         blob_gas_used: None,
         excess_blob_gas: None,
         parent_beacon_block_root: None,
+        requests_hash: None,
     }
 }
+
+/// Creates a chain of `length` consecutive Genesis era headers starting at block `start`, whose
+/// first header points to the zero hash.
+pub fn create_test_chain(start: u64, length: u64) -> Vec<BlockHeader> {
+    create_test_chain_genesis(&format!("{:?}", H256::zero()), start, length, 0)
+}
+
+/// Creates a chain of consecutive Genesis era headers with valid hashes and parent links.
+///
+/// The first header points to `parent_hash`, so that chains can be extended or forked. Headers
+/// only differ in their number, timestamp and `salt`, which is stored in the extra data so that
+/// forks at the same height get different hashes.
+pub fn create_test_chain_genesis(
+    parent_hash: &str,
+    start: u64,
+    length: u64,
+    salt: u8,
+) -> Vec<BlockHeader> {
+    let mut headers: Vec<BlockHeader> = vec![];
+    for number in start..start + length {
+        let parent_hash = headers
+            .last()
+            .map(|parent| parent.block_hash.clone())
+            .unwrap_or_else(|| parent_hash.to_string());
+        let mut header = BlockHeader {
+            block_hash: String::new(),
+            number: number as i64,
+            gas_limit: 5000,
+            gas_used: 0,
+            nonce: "0x0000000000000042".to_string(),
+            transaction_root: Some(format!("0x{}", "00".repeat(32))),
+            receipts_root: Some(format!("0x{}", "00".repeat(32))),
+            state_root: Some(format!("0x{}", "00".repeat(32))),
+            base_fee_per_gas: None,
+            parent_hash: Some(parent_hash),
+            ommers_hash: None,
+            miner: Some(format!("0x{}", "00".repeat(20))),
+            logs_bloom: Some(format!("0x{}", "00".repeat(256))),
+            difficulty: Some("0x400000000".to_string()),
+            totaldifficulty: None,
+            sha3_uncles: Some(format!("0x{}", "00".repeat(32))),
+            timestamp: Some(format!("{:#x}", number * 15)),
+            extra_data: Some(format!("0x{:02x}", salt)),
+            mix_hash: Some(format!("0x{}", "00".repeat(32))),
+            withdrawals_root: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            parent_beacon_block_root: None,
+            requests_hash: None,
+        };
+        let hash = BlockHeaderGenesis::from_db_header(header.clone())
+            .map(|header| header.compute_hash())
+            .unwrap_or_default();
+        header.block_hash = format!("{:?}", hash);
+        headers.push(header);
+    }
+    headers
+}
+
+/// Creates a chain of `length` consecutive Prague era headers starting at `PRAGUE_START`, with
+/// valid hashes and parent links.
+pub fn create_test_chain_prague(length: u64) -> Vec<BlockHeader> {
+    let mut headers: Vec<BlockHeader> = vec![];
+    for number in PRAGUE_START..PRAGUE_START + length {
+        let mut header = BlockHeader {
+            number: number as i64,
+            requests_hash: Some(format!("{:?}", H256::repeat_byte(0xe3))),
+            ..create_test_block_header_shapella()
+        };
+        if let Some(parent) = headers.last() {
+            header.parent_hash = Some(parent.block_hash.clone());
+        }
+        let encoded = encode_block_header(number, header.clone()).unwrap_or_default();
+        header.block_hash = format!("{:?}", keccak256(&encoded));
+        headers.push(header);
+    }
+    headers
+}